@@ -0,0 +1,118 @@
+use criterion::{
+    black_box,
+    criterion_group,
+    criterion_main,
+    BatchSize,
+    Criterion,
+};
+use silang::silang::{
+    Context,
+    Interpreter,
+    ScopeType,
+    Value,
+};
+use silang::{
+    parser,
+    preprocessor,
+};
+
+static LARGE_PROGRAM_FUNCTIONS: usize = 500;
+static LOOP_COUNT: usize = 1000;
+static FIB_N: usize = 15;
+static CONCAT_COUNT: usize = 1000;
+static SCOPE_DEPTH: usize = 1000;
+
+/// Returns program defining and calling many functions with nested expressions
+fn large_program() -> String {
+    let mut source = String::new();
+    for i in 0..LARGE_PROGRAM_FUNCTIONS {
+        source.push_str(&format!(
+            "# Function {i}\nf: function{i} (a b) int {{\n    :: c int\n    = c (+ (* a {i}) (- b (/ a 2)))\n    if (== c {i}) {{\n        return \"string {i}\\n\"\n    }}\n    return c\n}}\nprintln (function{i} {i} 1)\n",
+            i = i,
+        ));
+    }
+    source
+}
+
+fn parse(source_code: &str) -> parser::Program {
+    let source_code = preprocessor::preprocess(source_code).expect("preprocess");
+    parser::program_all_consuming(&source_code).expect("parse").1
+}
+
+/// Returns interpreter discarding output
+fn interpreter() -> Interpreter {
+    let mut interpreter = Interpreter::new();
+    interpreter.stdout_func = |_, _| {};
+    interpreter.stderr_func = |_, _| {};
+    interpreter
+}
+
+/// Benchmarks running `source_code` in a fresh interpreter
+fn bench_run(c: &mut Criterion, name: &str, source_code: &str) {
+    let program = parse(source_code);
+    c.bench_function(name, |b| {
+        b.iter_batched(
+            interpreter,
+            |mut interpreter| interpreter.run(black_box(&program)).expect("run"),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn parse_large_program(c: &mut Criterion) {
+    let source = large_program();
+    c.bench_function("parse_large_program", |b| b.iter(|| parse(black_box(&source))));
+}
+
+fn loop_arithmetic(c: &mut Criterion) {
+    let source = format!(
+        ":: (i sum) (int int)\nloop (== (== i {}) false) {{\n    = sum (+ sum (* i 2) (% i 7))\n    = i (+ i 1)\n}}\n",
+        LOOP_COUNT,
+    );
+    bench_run(c, "loop_arithmetic", &source);
+}
+
+fn recursive_fib(c: &mut Criterion) {
+    let source = format!(
+        "f: fib (n) int {{\n    if (== n 0) {{\n        return 0\n    }}\n    if (== n 1) {{\n        return 1\n    }}\n    return (+ (fib (- n 1)) (fib (- n 2)))\n}}\nfib {}\n",
+        FIB_N,
+    );
+    bench_run(c, "recursive_fib", &source);
+}
+
+fn string_concatenation(c: &mut Criterion) {
+    let source = format!(
+        ":: i int\n:: s string\nloop (== (== i {}) false) {{\n    = s (+ s \"abc\")\n    = i (+ i 1)\n}}\n",
+        CONCAT_COUNT,
+    );
+    bench_run(c, "string_concatenation", &source);
+}
+
+/// Looks up identifiers stored in the outermost and innermost of deep scopes
+fn deep_scope_lookup(c: &mut Criterion) {
+    let mut context = Context::new();
+    let outer = context.current_scope().scope_number;
+    context.store_identifier(outer, "outer", Value::new());
+    for _ in 0..SCOPE_DEPTH {
+        context.push_new(ScopeType::Block, false);
+    }
+    let inner = context.current_scope().scope_number;
+    context.store_identifier(inner, "inner", Value::new());
+
+    let mut group = c.benchmark_group("deep_scope_lookup");
+    group.bench_function("outer", |b| b.iter(|| context.search_identifier_id(black_box("outer"))));
+    group.bench_function("inner", |b| b.iter(|| context.search_identifier_id(black_box("inner"))));
+    group.bench_function("builtin", |b| b.iter(|| context.search_identifier_id(black_box("println"))));
+    group.bench_function("undefined", |b| b.iter(|| context.search_identifier_id(black_box("undefined"))));
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    parse_large_program,
+    loop_arithmetic,
+    recursive_fib,
+    string_concatenation,
+    deep_scope_lookup,
+);
+criterion_main!(benches);
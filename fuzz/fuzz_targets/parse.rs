@@ -0,0 +1,7 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use silang::parser;
+
+fuzz_target!(|source: &str| {
+    let _ = parser::program_all_consuming(source);
+});
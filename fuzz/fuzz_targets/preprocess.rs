@@ -0,0 +1,8 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use silang::preprocessor;
+
+fuzz_target!(|source: &str| {
+    let _ = preprocessor::preprocess(source);
+    let _ = preprocessor::is_incomplete(source);
+});
@@ -0,0 +1,31 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use silang::silang::Interpreter;
+use silang::{
+    parser,
+    preprocessor,
+};
+
+/// Statements and loop iterations run for each input
+static STEP_LIMIT: u64 = 10_000;
+/// Nested evaluation allowed for each input, kept low for sanitizer stack usage
+static DEPTH_LIMIT: usize = 128;
+
+fuzz_target!(|source: &str| {
+    let source_code = match preprocessor::preprocess(&format!("{}\n", source)) {
+        Ok(source_code) => source_code,
+        Err(_) => return,
+    };
+    let program = match parser::program_all_consuming(&source_code) {
+        Ok(program) => program.1,
+        Err(_) => return,
+    };
+    let mut interpreter = Interpreter::new();
+    interpreter.stdout_func = |_, _| {};
+    interpreter.stderr_func = |_, _| {};
+    interpreter.stdin_func = |_| None;
+    interpreter.file_system_enabled = false;
+    interpreter.step_limit = Some(STEP_LIMIT);
+    interpreter.depth_limit = Some(DEPTH_LIMIT);
+    let _ = interpreter.run(&program);
+});
@@ -0,0 +1,450 @@
+use crate::parser::{
+    Program,
+    Statement,
+    Expression,
+    Factor,
+    Block,
+    Span,
+};
+
+use serde_json::{
+    json,
+    Map,
+    Value as Json,
+};
+
+pub static JSON: &str = "json";
+pub static SEXP: &str = "sexp";
+
+/// 1-based line and column
+type Position = (usize, usize);
+
+/// Serializes `program` parsed from `source` in `format` (`json` or `sexp`)
+pub fn write_program(program: &Program, source: &str, format: &str) -> Result<String, String> {
+    if format == JSON {
+        match serde_json::to_string_pretty(&program_to_json(program, source)) {
+            Ok(s) => Ok(s),
+            Err(e) => Err(e.to_string()),
+        }
+    } else if format == SEXP {
+        Ok(program_to_sexp(program, source))
+    } else {
+        Err(format!("Unknown AST format: {}", format))
+    }
+}
+/// Deserializes program written by `write_program`
+pub fn read_program(s: &str, format: &str) -> Result<Program, String> {
+    if format == JSON {
+        match serde_json::from_str(s) {
+            Ok(json) => program_from_json(&json, &mut Spans::default()),
+            Err(e) => Err(e.to_string()),
+        }
+    } else if format == SEXP {
+        let mut reader = SexpReader { chars: s.chars().collect(), position: 0 };
+        let sexp = reader.read()?;
+        reader.skip_whitespace();
+        if reader.position != reader.chars.len() {
+            return Err("Unexpected trailing input".to_owned())
+        }
+        program_from_sexp(&sexp, &mut Spans::default())
+    } else {
+        Err(format!("Unknown AST format: {}", format))
+    }
+}
+
+fn empty_factor() -> Factor {
+    Factor { identifier: None, string: None, int: None, float: None, expression: None, block: None, span: Span::default() }
+}
+
+/// Returns 1-based line and column of `span`, or None for spans without position
+///
+/// Parsed statements and factors are followed by at least a newline, so only
+/// spans of nodes without position have no rest.
+fn position(span: &Span, source: &str) -> Option<Position> {
+    if span.position.is_none() && span.rest == 0 {
+        None
+    } else {
+        Some(span.line_column(source))
+    }
+}
+
+/// Gives spans to read nodes
+///
+/// Read nodes have no source, so spans hold their positions, and distinct
+/// rests in reading order tell statements apart like spans of parsed nodes do.
+/// Positions must not go back, as nodes are written in source order.
+#[derive(Default)]
+struct Spans {
+    count: usize,
+    last: Position,
+}
+impl Spans {
+    fn span(&mut self, position: Option<Position>) -> Result<Span, String> {
+        match position {
+            None => Ok(Span::default()),
+            Some((0, _)) | Some((_, 0)) => Err("line and column must be positive".to_owned()),
+            Some(position) if position < self.last => Err(format!("Position {}:{} is before {}:{}", position.0, position.1, self.last.0, self.last.1)),
+            Some(position) => {
+                self.count += 1;
+                self.last = position;
+                Ok(Span { rest: self.count, position: Some(position) })
+            },
+        }
+    }
+}
+
+// JSON
+//
+// Each node is an object with the field names of the parser structs.
+// Factor only has fields which are set, e.g. `{"identifier": "x"}`.
+// Statements and factors also have 1-based `line` and `column`.
+pub fn program_to_json(program: &Program, source: &str) -> Json {
+    let statements: Vec<Json> = program.statements.iter().map(|statement| statement_to_json(statement, source)).collect();
+    json!({ "statements": statements })
+}
+fn statement_to_json(statement: &Statement, source: &str) -> Json {
+    let mut object = Map::new();
+    object.insert("expression".to_owned(), expression_to_json(&statement.expression, source));
+    insert_position(&mut object, &statement.span, source);
+    Json::Object(object)
+}
+fn expression_to_json(expression: &Expression, source: &str) -> Json {
+    let factors: Vec<Json> = expression.factors.iter().map(|factor| factor_to_json(factor, source)).collect();
+    json!({ "factors": factors })
+}
+fn insert_position(object: &mut Map<String, Json>, span: &Span, source: &str) {
+    if let Some((line, column)) = position(span, source) {
+        object.insert("line".to_owned(), json!(line));
+        object.insert("column".to_owned(), json!(column));
+    }
+}
+fn factor_to_json(factor: &Factor, source: &str) -> Json {
+    let mut object = Map::new();
+    insert_position(&mut object, &factor.span, source);
+    if let Some(identifier) = &factor.identifier {
+        object.insert("identifier".to_owned(), json!(identifier));
+    }
+    if let Some(string) = &factor.string {
+        object.insert("string".to_owned(), json!(string));
+    }
+    if let Some(int) = factor.int {
+        object.insert("int".to_owned(), json!(int));
+    }
+    if let Some(float) = factor.float {
+        if float.is_finite() {
+            object.insert("float".to_owned(), json!(float));
+        } else {
+            object.insert("float".to_owned(), json!(float.to_string()));
+        }
+    }
+    if let Some(expression) = &factor.expression {
+        object.insert("expression".to_owned(), expression_to_json(expression, source));
+    }
+    if let Some(block) = &factor.block {
+        object.insert("block".to_owned(), json!({ "program": program_to_json(&block.program, source) }));
+    }
+    Json::Object(object)
+}
+
+fn program_from_json(json: &Json, spans: &mut Spans) -> Result<Program, String> {
+    let mut statements = Vec::new();
+    for statement in json_array(json, "statements")? {
+        let span = spans.span(position_from_json(statement)?)?;
+        statements.push(Statement { expression: expression_from_json(json_field(statement, "expression")?, spans)?, span });
+    }
+    Ok(Program { statements })
+}
+fn expression_from_json(json: &Json, spans: &mut Spans) -> Result<Expression, String> {
+    let mut factors = Vec::new();
+    for factor in json_array(json, "factors")? {
+        factors.push(factor_from_json(factor, spans)?);
+    }
+    Ok(Expression { factors })
+}
+fn factor_from_json(json: &Json, spans: &mut Spans) -> Result<Factor, String> {
+    let object = match json.as_object() {
+        Some(object) => object,
+        None => return Err("Factor must be object".to_owned()),
+    };
+    let mut factor = empty_factor();
+    factor.span = spans.span(position_from_json(json)?)?;
+    for (key, value) in object {
+        match key.as_str() {
+            "line" | "column" => {},
+            "identifier" => factor.identifier = Some(json_string(value)?),
+            "string" => factor.string = Some(json_string(value)?),
+            "int" => match value.as_i64() {
+                Some(int) => factor.int = Some(int),
+                None => return Err("int must be integer".to_owned()),
+            },
+            "float" => match value {
+                Json::Number(number) => factor.float = number.as_f64(),
+                Json::String(string) => match string.parse() {
+                    Ok(float) => factor.float = Some(float),
+                    Err(_) => return Err("float must be number".to_owned()),
+                },
+                _ => return Err("float must be number".to_owned()),
+            },
+            "expression" => factor.expression = Some(expression_from_json(value, spans)?),
+            "block" => factor.block = Some(Block { program: program_from_json(json_field(value, "program")?, spans)? }),
+            _ => return Err(format!("Unknown factor field: {}", key)),
+        }
+    }
+    Ok(factor)
+}
+/// Returns `line` and `column` of statement or factor if it has both
+fn position_from_json(json: &Json) -> Result<Option<Position>, String> {
+    match (json.get("line"), json.get("column")) {
+        (None, None) => Ok(None),
+        (Some(line), Some(column)) => match (line.as_u64(), column.as_u64()) {
+            (Some(line), Some(column)) => Ok(Some((line as usize, column as usize))),
+            _ => Err("line and column must be integers".to_owned()),
+        },
+        _ => Err("line and column must be given together".to_owned()),
+    }
+}
+fn json_field<'a>(json: &'a Json, name: &str) -> Result<&'a Json, String> {
+    match json.get(name) {
+        Some(value) => Ok(value),
+        None => Err(format!("Missing field: {}", name)),
+    }
+}
+fn json_array<'a>(json: &'a Json, name: &str) -> Result<&'a Vec<Json>, String> {
+    match json_field(json, name)?.as_array() {
+        Some(array) => Ok(array),
+        None => Err(format!("{} must be array", name)),
+    }
+}
+fn json_string(json: &Json) -> Result<String, String> {
+    match json.as_str() {
+        Some(string) => Ok(string.to_owned()),
+        None => Err("Value must be string".to_owned()),
+    }
+}
+
+
+// S-expression
+//
+// (program (statement (at 1 1) (expression (identifier (at 1 1) "println") (string (at 1 9) "hi"))))
+// Index access is written as `(identifier (at 1 1) "x" (expression ...))`.
+// `(at LINE COLUMN)` gives 1-based position of statement or factor.
+pub fn program_to_sexp(program: &Program, source: &str) -> String {
+    let mut buffer = String::from("(program");
+    for statement in &program.statements {
+        buffer.push_str(" (statement");
+        buffer.push_str(&position_to_sexp(&statement.span, source));
+        buffer.push(' ');
+        buffer.push_str(&expression_to_sexp(&statement.expression, "", source));
+        buffer.push(')');
+    }
+    buffer.push(')');
+    buffer
+}
+/// Writes `expression` with `position` written by `position_to_sexp`
+fn expression_to_sexp(expression: &Expression, position: &str, source: &str) -> String {
+    let mut buffer = String::from("(expression");
+    buffer.push_str(position);
+    for factor in &expression.factors {
+        buffer.push(' ');
+        buffer.push_str(&factor_to_sexp(factor, source));
+    }
+    buffer.push(')');
+    buffer
+}
+fn position_to_sexp(span: &Span, source: &str) -> String {
+    match position(span, source) {
+        Some((line, column)) => format!(" (at {} {})", line, column),
+        None => String::new(),
+    }
+}
+fn factor_to_sexp(factor: &Factor, source: &str) -> String {
+    let at = position_to_sexp(&factor.span, source);
+    if let Some(identifier) = &factor.identifier {
+        match &factor.expression {
+            Some(expression) => format!("(identifier{} {} {})", at, quote(identifier), expression_to_sexp(expression, "", source)),
+            None => format!("(identifier{} {})", at, quote(identifier)),
+        }
+    } else if let Some(string) = &factor.string {
+        format!("(string{} {})", at, quote(string))
+    } else if let Some(int) = factor.int {
+        format!("(int{} {})", at, int)
+    } else if let Some(float) = factor.float {
+        format!("(float{} {})", at, float)
+    } else if let Some(expression) = &factor.expression {
+        expression_to_sexp(expression, &at, source)
+    } else if let Some(block) = &factor.block {
+        format!("(block{} {})", at, program_to_sexp(&block.program, source))
+    } else {
+        format!("(expression{})", at)
+    }
+}
+fn quote(s: &str) -> String {
+    let mut buffer = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => buffer.push_str("\\\""),
+            '\\' => buffer.push_str("\\\\"),
+            '\n' => buffer.push_str("\\n"),
+            '\r' => buffer.push_str("\\r"),
+            '\t' => buffer.push_str("\\t"),
+            _ => buffer.push(c),
+        }
+    }
+    buffer.push('"');
+    buffer
+}
+
+#[derive(Debug)]
+enum Sexp {
+    Atom(String),
+    String(String),
+    List(Vec<Sexp>),
+}
+
+struct SexpReader {
+    chars: Vec<char>,
+    position: usize,
+}
+impl SexpReader {
+    fn skip_whitespace(&mut self) {
+        while self.position < self.chars.len() && self.chars[self.position].is_whitespace() {
+            self.position += 1;
+        }
+    }
+    fn read(&mut self) -> Result<Sexp, String> {
+        self.skip_whitespace();
+        match self.chars.get(self.position) {
+            None => Err("Unexpected end of input".to_owned()),
+            Some('(') => {
+                self.position += 1;
+                let mut list = Vec::new();
+                loop {
+                    self.skip_whitespace();
+                    match self.chars.get(self.position) {
+                        None => return Err("Unclosed list".to_owned()),
+                        Some(')') => {
+                            self.position += 1;
+                            return Ok(Sexp::List(list))
+                        },
+                        Some(_) => list.push(self.read()?),
+                    }
+                }
+            },
+            Some(')') => Err(format!("Unexpected ) at {}", self.position)),
+            Some('"') => {
+                self.position += 1;
+                let mut string = String::new();
+                loop {
+                    match self.chars.get(self.position) {
+                        None => return Err("Unclosed string".to_owned()),
+                        Some('"') => {
+                            self.position += 1;
+                            return Ok(Sexp::String(string))
+                        },
+                        Some('\\') => {
+                            self.position += 1;
+                            match self.chars.get(self.position) {
+                                Some('n') => string.push('\n'),
+                                Some('r') => string.push('\r'),
+                                Some('t') => string.push('\t'),
+                                Some(c) => string.push(*c),
+                                None => return Err("Unclosed string".to_owned()),
+                            }
+                        },
+                        Some(c) => string.push(*c),
+                    }
+                    self.position += 1;
+                }
+            },
+            Some(_) => {
+                let start = self.position;
+                while self.position < self.chars.len() {
+                    let c = self.chars[self.position];
+                    if c.is_whitespace() || c == '(' || c == ')' || c == '"' {
+                        break;
+                    }
+                    self.position += 1;
+                }
+                Ok(Sexp::Atom(self.chars[start..self.position].iter().collect()))
+            },
+        }
+    }
+}
+
+/// Splits `(head item...)` into items after checking head
+fn sexp_list<'a>(sexp: &'a Sexp, head: &str) -> Result<&'a [Sexp], String> {
+    if let Sexp::List(list) = sexp {
+        if let Some(Sexp::Atom(atom)) = list.first() {
+            if atom == head {
+                return Ok(&list[1..])
+            }
+        }
+    }
+    Err(format!("Expected ({} ...)", head))
+}
+/// Splits leading `(at LINE COLUMN)` from `items`
+fn split_position(items: &[Sexp]) -> Result<(Option<Position>, &[Sexp]), String> {
+    if let Some(first) = items.first() {
+        if let Ok(at) = sexp_list(first, "at") {
+            return match at {
+                [Sexp::Atom(line), Sexp::Atom(column)] => match (line.parse(), column.parse()) {
+                    (Ok(line), Ok(column)) => Ok((Some((line, column)), &items[1..])),
+                    _ => Err(format!("Invalid position: {} {}", line, column)),
+                },
+                _ => Err("at must have line and column".to_owned()),
+            }
+        }
+    }
+    Ok((None, items))
+}
+fn program_from_sexp(sexp: &Sexp, spans: &mut Spans) -> Result<Program, String> {
+    let mut statements = Vec::new();
+    for statement in sexp_list(sexp, "program")? {
+        let (position, items) = split_position(sexp_list(statement, "statement")?)?;
+        if items.len() != 1 {
+            return Err("statement must have 1 expression".to_owned())
+        }
+        let span = spans.span(position)?;
+        statements.push(Statement { expression: expression_from_sexp(&items[0], spans)?, span });
+    }
+    Ok(Program { statements })
+}
+fn expression_from_sexp(sexp: &Sexp, spans: &mut Spans) -> Result<Expression, String> {
+    let mut factors = Vec::new();
+    for factor in split_position(sexp_list(sexp, "expression")?)?.1 {
+        factors.push(factor_from_sexp(factor, spans)?);
+    }
+    Ok(Expression { factors })
+}
+fn factor_from_sexp(sexp: &Sexp, spans: &mut Spans) -> Result<Factor, String> {
+    let head = match sexp {
+        Sexp::List(list) => match list.first() {
+            Some(Sexp::Atom(atom)) => atom.as_str(),
+            _ => return Err("Factor must start with name".to_owned()),
+        },
+        _ => return Err("Factor must be list".to_owned()),
+    };
+    let (position, items) = split_position(sexp_list(sexp, head)?)?;
+    let mut factor = empty_factor();
+    factor.span = spans.span(position)?;
+    match (head, items) {
+        ("identifier", [Sexp::String(name)]) => factor.identifier = Some(name.clone()),
+        ("identifier", [Sexp::String(name), index]) => {
+            factor.identifier = Some(name.clone());
+            factor.expression = Some(expression_from_sexp(index, spans)?);
+        },
+        ("string", [Sexp::String(string)]) => factor.string = Some(string.clone()),
+        ("int", [Sexp::Atom(int)]) => match int.parse() {
+            Ok(int) => factor.int = Some(int),
+            Err(_) => return Err(format!("Invalid int: {}", int)),
+        },
+        ("float", [Sexp::Atom(float)]) => match float.parse() {
+            Ok(float) => factor.float = Some(float),
+            Err(_) => return Err(format!("Invalid float: {}", float)),
+        },
+        ("expression", _) => factor.expression = Some(expression_from_sexp(sexp, spans)?),
+        ("block", [program]) => factor.block = Some(Block { program: program_from_sexp(program, spans)? }),
+        _ => return Err(format!("Invalid factor: {}", head)),
+    }
+    Ok(factor)
+}
@@ -0,0 +1,4 @@
+//! Debug adapter for SILang speaking DAP over stdio
+fn main() {
+    std::process::exit(silang::dap::run());
+}
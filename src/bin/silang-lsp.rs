@@ -0,0 +1,4 @@
+//! Language server for SILang speaking JSON-RPC over stdio
+fn main() {
+    std::process::exit(silang::lsp::run());
+}
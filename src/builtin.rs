@@ -3,8 +3,13 @@ pub mod declare;
 pub mod function;
 pub mod types;
 pub mod print;
+pub mod input;
+#[cfg(any(target_family = "unix", target_family = "windows"))]
+pub mod file;
 pub mod arithmetic;
 pub mod compare;
+pub mod assert;
+pub mod system;
 use crate::silang::{
     Interpreter,
     Value,
@@ -12,10 +17,115 @@ use crate::silang::{
     EvalResult,
 };
 use crate::parser;
+use crate::define;
+
+/// Builtin function stored in root scope
+///
+/// Argument lengths exclude the function itself.
+/// If `evaluates_args` is true, they are counted after evaluating arguments,
+/// otherwise they are counted as written.
+pub struct BuiltinFunction {
+    pub name: &'static str,
+    pub function: fn (&mut Interpreter, &[Value]) -> Result<EvalReturn, String>,
+    pub min_args: usize,
+    pub max_args: Option<usize>,
+    pub evaluates_args: bool,
+}
+impl BuiltinFunction {
+    fn new(name: &'static str, function: fn (&mut Interpreter, &[Value]) -> Result<EvalReturn, String>, min_args: usize, max_args: Option<usize>, evaluates_args: bool) -> Self {
+        BuiltinFunction { name, function, min_args, max_args, evaluates_args }
+    }
+}
+
+/// Returns all builtin functions
+pub fn builtin_functions() -> Vec<BuiltinFunction> {
+    let mut functions = vec![
+        // Functions
+        BuiltinFunction::new(define::IMPORT, Interpreter::import, 1, None, true),
+        BuiltinFunction::new(define::LAMBDA, Interpreter::lambda, 2, Some(2), false),
+        BuiltinFunction::new(define::RETURN, Interpreter::return_expression, 0, None, true),
+        // control
+        BuiltinFunction::new(define::UNTYPED, Interpreter::untyped, 1, Some(1), false),
+        BuiltinFunction::new(define::IF, Interpreter::if_expression, 2, Some(3), false),
+        BuiltinFunction::new(define::LOOP, Interpreter::loop_expression, 2, Some(2), false),
+        // Declare
+        BuiltinFunction::new(define::DECAS, Interpreter::decas, 2, Some(2), false),
+        BuiltinFunction::new(define::DECAS_ALIAS, Interpreter::decas, 2, Some(2), false),
+        BuiltinFunction::new(define::FUNCTION_DEFINITION, Interpreter::define_function, 3, Some(4), false),
+        // Assign
+        BuiltinFunction::new(define::ASSIGN, Interpreter::assign, 2, Some(2), false),
+        BuiltinFunction::new(define::ASSIGN_DEFER, Interpreter::assign_defer, 2, Some(2), false),
+        // Print
+        BuiltinFunction::new(define::PRINT, Interpreter::print, 0, None, true),
+        BuiltinFunction::new(define::PRINTLN, Interpreter::println, 0, None, true),
+        BuiltinFunction::new(define::EPRINT, Interpreter::eprint, 0, None, true),
+        BuiltinFunction::new(define::EPRINTLN, Interpreter::eprintln, 0, None, true),
+        // Input
+        BuiltinFunction::new(define::INPUT, Interpreter::input, 0, None, true),
+        BuiltinFunction::new(define::READ_LINE, Interpreter::read_line, 0, Some(0), false),
+        BuiltinFunction::new(define::READ_ALL, Interpreter::read_all, 0, Some(0), false),
+        BuiltinFunction::new(define::EOF, Interpreter::eof, 0, Some(0), false),
+        // System
+        BuiltinFunction::new(define::EXIT, Interpreter::exit, 0, Some(1), true),
+        // Arithmetic
+        BuiltinFunction::new(define::ADD, Interpreter::add, 2, None, true),
+        BuiltinFunction::new(define::SUB, Interpreter::sub, 2, None, true),
+        BuiltinFunction::new(define::MUL, Interpreter::mul, 2, None, true),
+        BuiltinFunction::new(define::DIV, Interpreter::div, 2, None, true),
+        BuiltinFunction::new(define::REM, Interpreter::rem, 2, Some(2), true),
+        BuiltinFunction::new(define::WRAPPING_ADD, Interpreter::wrapping_add, 2, None, true),
+        BuiltinFunction::new(define::WRAPPING_SUB, Interpreter::wrapping_sub, 2, None, true),
+        BuiltinFunction::new(define::WRAPPING_MUL, Interpreter::wrapping_mul, 2, None, true),
+        BuiltinFunction::new(define::SATURATING_ADD, Interpreter::saturating_add, 2, None, true),
+        BuiltinFunction::new(define::SATURATING_SUB, Interpreter::saturating_sub, 2, None, true),
+        BuiltinFunction::new(define::SATURATING_MUL, Interpreter::saturating_mul, 2, None, true),
+        // Bitwise
+        BuiltinFunction::new(define::BIT_AND, Interpreter::bit_and, 2, None, true),
+        BuiltinFunction::new(define::BIT_OR, Interpreter::bit_or, 2, None, true),
+        BuiltinFunction::new(define::BIT_XOR, Interpreter::bit_xor, 2, None, true),
+        BuiltinFunction::new(define::BIT_NOT, Interpreter::bit_not, 1, Some(1), true),
+        BuiltinFunction::new(define::SHIFT_LEFT, Interpreter::shift_left, 2, Some(2), true),
+        BuiltinFunction::new(define::SHIFT_RIGHT, Interpreter::shift_right, 2, Some(2), true),
+        BuiltinFunction::new(define::SHIFT_RIGHT_LOGICAL, Interpreter::shift_right_logical, 2, Some(2), true),
+        BuiltinFunction::new(define::POPCOUNT, Interpreter::popcount, 1, Some(1), true),
+        BuiltinFunction::new(define::LEADING_ZEROS, Interpreter::leading_zeros, 1, Some(1), true),
+        BuiltinFunction::new(define::TRAILING_ZEROS, Interpreter::trailing_zeros, 1, Some(1), true),
+        // Compare
+        BuiltinFunction::new(define::EQUAL, Interpreter::equal, 2, None, false),
+        // Assert
+        BuiltinFunction::new(define::ASSERT, Interpreter::assert, 1, Some(2), true),
+        BuiltinFunction::new(define::ASSERT_EQ, Interpreter::assert_eq, 2, Some(3), true),
+        BuiltinFunction::new(define::ASSERT_ERROR, Interpreter::assert_error, 1, Some(2), false),
+    ];
+    #[cfg(any(target_family = "unix", target_family = "windows"))]
+    functions.extend(vec![
+        // System
+        BuiltinFunction::new(define::ENV, Interpreter::env, 1, Some(1), true),
+        BuiltinFunction::new(define::SET_ENV, Interpreter::set_env, 2, Some(2), true),
+        // File system
+        BuiltinFunction::new(define::READ_FILE, Interpreter::read_file, 1, Some(1), true),
+        BuiltinFunction::new(define::READ_LINES, Interpreter::read_lines, 1, Some(1), true),
+        BuiltinFunction::new(define::WRITE_FILE, Interpreter::write_file, 2, Some(2), true),
+        BuiltinFunction::new(define::APPEND_FILE, Interpreter::append_file, 2, Some(2), true),
+        BuiltinFunction::new(define::EXISTS, Interpreter::exists, 1, Some(1), true),
+        BuiltinFunction::new(define::REMOVE_FILE, Interpreter::remove_file, 1, Some(1), true),
+        BuiltinFunction::new(define::LIST_DIR, Interpreter::list_dir, 1, Some(1), true),
+        BuiltinFunction::new(define::MKDIR, Interpreter::mkdir, 1, Some(1), true),
+    ]);
+    functions
+}
+
+/// Returns file `import` reads `module` from, relative to the working directory
+pub fn module_file_name(module: &str) -> String {
+    format!("{}.sil", module)
+}
 
 impl Interpreter {
     #[cfg(any(target_family = "unix", target_family = "windows"))]
     pub fn import(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        if !self.file_system_enabled {
+            return Err(format!("{}: {}", define::IMPORT, define::FILE_SYSTEM_DISABLED))
+        }
         let mut module_names = Vec::new();
         for arg in &args[1..] {
             match self.eval_value(arg, true) {
@@ -45,7 +155,7 @@ impl Interpreter {
                         Err(_) => {},
                     }
                 }
-                let file_name = format!("{}.sil", module_name_string);
+                let file_name = module_file_name(&module_name_string);
                 let mut buffer;
                 match std::fs::read_to_string(&file_name) {
                     Ok(s) => {
@@ -59,7 +169,11 @@ impl Interpreter {
                 let parse_result = parser::program_all_consuming(&buffer);
                 match parse_result {
                     Ok(program) => {
-                        match self.run(&program.1) {
+                        let current_source = self.current_source;
+                        self.current_source = Some(self.add_source_file(&file_name, &buffer));
+                        let result = self.run(&program.1);
+                        self.current_source = current_source;
+                        match result {
                             Ok(_) => {},
                             Err(e) => {
                                 return Err(e)
@@ -6,6 +6,12 @@ use crate::silang::{
     SILType,
 };
 use crate::define;
+use num_bigint::BigInt;
+use num_traits::{
+    Zero,
+    ToPrimitive,
+    FromPrimitive,
+};
 
 impl Interpreter {
     pub fn add(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
@@ -45,10 +51,23 @@ impl Interpreter {
             retval.string = Some(string);
             retval.sil_type = SILType::String;
             Ok(retval)
-        } else if lhs.int.is_some() && rhs.int.is_some() {
-            retval.int = Some(lhs.int.unwrap() + rhs.int.unwrap());
-            retval.sil_type = SILType::Int;
+        } else if let Some((lhs_bigint, rhs_bigint)) = bigint_operands(lhs, rhs) {
+            retval.bigint = Some(lhs_bigint + rhs_bigint);
+            retval.sil_type = SILType::BigInt;
+            Ok(retval)
+        } else if let Some((lhs_float, rhs_float)) = bigint_float_operands(lhs, rhs) {
+            retval.float = Some(lhs_float + rhs_float);
+            retval.sil_type = SILType::Float;
             Ok(retval)
+        } else if let Some((lhs_int, rhs_int)) = int_operands(lhs, rhs) {
+            match lhs_int.checked_add(rhs_int) {
+                Some(int) => {
+                    retval.int = Some(int);
+                    retval.sil_type = SILType::Int;
+                    Ok(retval)
+                },
+                None => Err(format!("add: {}", define::INTEGER_OVERFLOW)),
+            }
         } else if lhs.float.is_some() && rhs.float.is_some() {
             retval.float = Some(lhs.float.unwrap() + rhs.float.unwrap());
             retval.sil_type = SILType::Float;
@@ -95,11 +114,23 @@ impl Interpreter {
     }
     pub fn sub_value(&self, lhs: &Value, rhs: &Value) -> Result<Value, String> {
         let mut retval = Value::new();
-        if let Some(lhs_int) = lhs.int {
-            if let Some(rhs_int) = rhs.int {
-                retval.int = Some(lhs_int - rhs_int);
-                Ok(retval)
-            } else if let Some(rhs_float) = rhs.float {
+        if let Some((lhs_bigint, rhs_bigint)) = bigint_operands(lhs, rhs) {
+            retval.bigint = Some(lhs_bigint - rhs_bigint);
+            retval.sil_type = SILType::BigInt;
+            Ok(retval)
+        } else if let Some((lhs_float, rhs_float)) = bigint_float_operands(lhs, rhs) {
+            retval.float = Some(lhs_float - rhs_float);
+            Ok(retval)
+        } else if let Some((lhs_int, rhs_int)) = int_operands(lhs, rhs) {
+            match lhs_int.checked_sub(rhs_int) {
+                Some(int) => {
+                    retval.int = Some(int);
+                    Ok(retval)
+                },
+                None => Err(format!("sub: {}", define::INTEGER_OVERFLOW)),
+            }
+        } else if let Some(lhs_int) = lhs.int {
+            if let Some(rhs_float) = rhs.float {
                 retval.float = Some(lhs_int as f64 - rhs_float);
                 Ok(retval)
             } else {
@@ -149,11 +180,23 @@ impl Interpreter {
     }
     pub fn mul_value(&self, lhs: &Value, rhs: &Value) -> Result<Value, String> {
         let mut retval = Value::new();
-        if let Some(lhs_int) = lhs.int {
-            if let Some(rhs_int) = rhs.int {
-                retval.int = Some(lhs_int * rhs_int);
-                Ok(retval)
-            } else if let Some(rhs_float) = rhs.float {
+        if let Some((lhs_bigint, rhs_bigint)) = bigint_operands(lhs, rhs) {
+            retval.bigint = Some(lhs_bigint * rhs_bigint);
+            retval.sil_type = SILType::BigInt;
+            Ok(retval)
+        } else if let Some((lhs_float, rhs_float)) = bigint_float_operands(lhs, rhs) {
+            retval.float = Some(lhs_float * rhs_float);
+            Ok(retval)
+        } else if let Some((lhs_int, rhs_int)) = int_operands(lhs, rhs) {
+            match lhs_int.checked_mul(rhs_int) {
+                Some(int) => {
+                    retval.int = Some(int);
+                    Ok(retval)
+                },
+                None => Err(format!("mul: {}", define::INTEGER_OVERFLOW)),
+            }
+        } else if let Some(lhs_int) = lhs.int {
+            if let Some(rhs_float) = rhs.float {
                 retval.float = Some(lhs_int as f64 * rhs_float);
                 Ok(retval)
             } else {
@@ -203,15 +246,33 @@ impl Interpreter {
     }
     pub fn div_value(&self, lhs: &Value, rhs: &Value) -> Result<Value, String> {
         let mut retval = Value::new();
-        if let Some(lhs_int) = lhs.int {
-            if let Some(rhs_int) = rhs.int {
-                retval.int = Some(lhs_int / rhs_int);
-                Ok(retval)
-            } else if let Some(rhs_float) = rhs.float {
+        if let Some((lhs_bigint, rhs_bigint)) = bigint_operands(lhs, rhs) {
+            if rhs_bigint.is_zero() {
+                return Err(format!("div: {}", define::DIVISION_BY_ZERO))
+            }
+            retval.bigint = Some(lhs_bigint / rhs_bigint);
+            retval.sil_type = SILType::BigInt;
+            Ok(retval)
+        } else if let Some((lhs_float, rhs_float)) = bigint_float_operands(lhs, rhs) {
+            retval.float = Some(lhs_float / rhs_float);
+            Ok(retval)
+        } else if let Some((lhs_int, rhs_int)) = int_operands(lhs, rhs) {
+            if rhs_int == 0 {
+                return Err(format!("div: {}", define::DIVISION_BY_ZERO))
+            }
+            match lhs_int.checked_div(rhs_int) {
+                Some(int) => {
+                    retval.int = Some(int);
+                    Ok(retval)
+                },
+                None => Err(format!("div: {}", define::INTEGER_OVERFLOW)),
+            }
+        } else if let Some(lhs_int) = lhs.int {
+            if let Some(rhs_float) = rhs.float {
                 retval.float = Some(lhs_int as f64 / rhs_float);
                 Ok(retval)
             } else {
-                Err(format!("mul: {}", define::UNSUPPORTED_OPERATION))
+                Err(format!("div: {}", define::UNSUPPORTED_OPERATION))
             }
         } else if let Some(lhs_float) = lhs.float {
             if let Some(rhs_int) = rhs.int {
@@ -221,10 +282,10 @@ impl Interpreter {
                 retval.float = Some(lhs_float / rhs_float);
                 Ok(retval)
             } else {
-                Err(format!("mul: {}", define::UNSUPPORTED_OPERATION))
+                Err(format!("div: {}", define::UNSUPPORTED_OPERATION))
             }
         } else {
-            Err(format!("mul: {}", define::UNSUPPORTED_OPERATION))
+            Err(format!("div: {}", define::UNSUPPORTED_OPERATION))
         }
     }
     pub fn rem(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
@@ -254,11 +315,29 @@ impl Interpreter {
     }
     pub fn rem_value(&self, lhs: &Value, rhs: &Value) -> Result<Value, String> {
         let mut retval = Value::new();
-        if let Some(lhs_int) = lhs.int {
-            if let Some(rhs_int) = rhs.int {
-                retval.int = Some(lhs_int % rhs_int);
-                Ok(retval)
-            } else if let Some(rhs_float) = rhs.float {
+        if let Some((lhs_bigint, rhs_bigint)) = bigint_operands(lhs, rhs) {
+            if rhs_bigint.is_zero() {
+                return Err(format!("rem: {}", define::DIVISION_BY_ZERO))
+            }
+            retval.bigint = Some(lhs_bigint % rhs_bigint);
+            retval.sil_type = SILType::BigInt;
+            Ok(retval)
+        } else if let Some((lhs_float, rhs_float)) = bigint_float_operands(lhs, rhs) {
+            retval.float = Some(lhs_float % rhs_float);
+            Ok(retval)
+        } else if let Some((lhs_int, rhs_int)) = int_operands(lhs, rhs) {
+            if rhs_int == 0 {
+                return Err(format!("rem: {}", define::DIVISION_BY_ZERO))
+            }
+            match lhs_int.checked_rem(rhs_int) {
+                Some(int) => {
+                    retval.int = Some(int);
+                    Ok(retval)
+                },
+                None => Err(format!("rem: {}", define::INTEGER_OVERFLOW)),
+            }
+        } else if let Some(lhs_int) = lhs.int {
+            if let Some(rhs_float) = rhs.float {
                 retval.float = Some(lhs_int as f64 % rhs_float);
                 Ok(retval)
             } else {
@@ -278,4 +357,173 @@ impl Interpreter {
             Err(format!("rem: {}", define::UNSUPPORTED_OPERATION))
         }
     }
+
+    pub fn wrapping_add(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        self.integer_arithmetic(define::WRAPPING_ADD, args, i64::wrapping_add)
+    }
+    pub fn wrapping_sub(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        self.integer_arithmetic(define::WRAPPING_SUB, args, i64::wrapping_sub)
+    }
+    pub fn wrapping_mul(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        self.integer_arithmetic(define::WRAPPING_MUL, args, i64::wrapping_mul)
+    }
+    pub fn saturating_add(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        self.integer_arithmetic(define::SATURATING_ADD, args, i64::saturating_add)
+    }
+    pub fn saturating_sub(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        self.integer_arithmetic(define::SATURATING_SUB, args, i64::saturating_sub)
+    }
+    pub fn saturating_mul(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        self.integer_arithmetic(define::SATURATING_MUL, args, i64::saturating_mul)
+    }
+    pub fn bit_and(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        self.integer_arithmetic(define::BIT_AND, args, |lhs, rhs| lhs & rhs)
+    }
+    pub fn bit_or(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        self.integer_arithmetic(define::BIT_OR, args, |lhs, rhs| lhs | rhs)
+    }
+    pub fn bit_xor(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        self.integer_arithmetic(define::BIT_XOR, args, |lhs, rhs| lhs ^ rhs)
+    }
+    pub fn bit_not(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        self.integer_unary(define::BIT_NOT, args, |int| !int)
+    }
+    pub fn shift_left(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        self.integer_shift(define::SHIFT_LEFT, args, |int, amount| int << amount)
+    }
+    pub fn shift_right(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        self.integer_shift(define::SHIFT_RIGHT, args, |int, amount| int >> amount)
+    }
+    pub fn shift_right_logical(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        self.integer_shift(define::SHIFT_RIGHT_LOGICAL, args, |int, amount| ((int as u64) >> amount) as i64)
+    }
+    pub fn popcount(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        self.integer_unary(define::POPCOUNT, args, |int| int.count_ones() as i64)
+    }
+    pub fn leading_zeros(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        self.integer_unary(define::LEADING_ZEROS, args, |int| int.leading_zeros() as i64)
+    }
+    pub fn trailing_zeros(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        self.integer_unary(define::TRAILING_ZEROS, args, |int| int.trailing_zeros() as i64)
+    }
+
+    /// Folds int arguments from left to right with `operation`
+    fn integer_arithmetic(&mut self, name: &str, args: &[Value], operation: fn (i64, i64) -> i64) -> Result<EvalReturn, String> {
+        let ints = self.integer_arguments(name, args)?;
+        if ints.len() < 2 {
+            return Err(format!("{}: Argument length must be >=2", name))
+        }
+        let mut accumulator = ints[0];
+        for int in &ints[1..] {
+            accumulator = operation(accumulator, *int);
+        }
+        Ok(integer_return(accumulator))
+    }
+    fn integer_unary(&mut self, name: &str, args: &[Value], operation: fn (i64) -> i64) -> Result<EvalReturn, String> {
+        let ints = self.integer_arguments(name, args)?;
+        if ints.len() != 1 {
+            return Err(format!("{}: Argument length must be 1", name))
+        }
+        Ok(integer_return(operation(ints[0])))
+    }
+    fn integer_shift(&mut self, name: &str, args: &[Value], operation: fn (i64, u32) -> i64) -> Result<EvalReturn, String> {
+        let ints = self.integer_arguments(name, args)?;
+        if ints.len() != 2 {
+            return Err(format!("{}: Argument length must be 2", name))
+        }
+        if ints[1] < 0 || 64 <= ints[1] {
+            return Err(format!("{}: {}", name, define::INVALID_SHIFT_AMOUNT))
+        }
+        Ok(integer_return(operation(ints[0], ints[1] as u32)))
+    }
+    fn integer_arguments(&mut self, name: &str, args: &[Value]) -> Result<Vec<i64>, String> {
+        let mut ints = Vec::new();
+        for value in &args[1..] {
+            match self.eval_value(value, true) {
+                Ok(result) => {
+                    for v in result.values {
+                        ints.push(self.integer_operand(name, &v)?);
+                    }
+                },
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(ints)
+    }
+    /// Returns int value of `value`
+    ///
+    /// Number literals are parsed as float, so floats without fractional part are accepted.
+    pub fn integer_operand(&self, name: &str, value: &Value) -> Result<i64, String> {
+        if let Some(int) = value.int {
+            Ok(int)
+        } else if value.float.is_some() {
+            integral_float(value).ok_or_else(|| format!("{}: Argument must be integer", name))
+        } else {
+            Err(format!("{}: {}", name, define::UNSUPPORTED_OPERATION))
+        }
+    }
+}
+
+/// Returns both operands as bigint if one is bigint and the other is integer
+///
+/// Floats without fractional part are treated as integer like number literals.
+pub fn bigint_operands(lhs: &Value, rhs: &Value) -> Option<(BigInt, BigInt)> {
+    match (&lhs.bigint, &rhs.bigint) {
+        (Some(lhs_bigint), Some(rhs_bigint)) => Some((lhs_bigint.clone(), rhs_bigint.clone())),
+        (Some(lhs_bigint), None) => to_bigint(rhs).map(|rhs_bigint| (lhs_bigint.clone(), rhs_bigint)),
+        (None, Some(rhs_bigint)) => to_bigint(lhs).map(|lhs_bigint| (lhs_bigint, rhs_bigint.clone())),
+        (None, None) => None,
+    }
+}
+/// Returns both operands as int if one is int and the other is integer
+///
+/// Floats without fractional part are treated as integer like number literals.
+pub fn int_operands(lhs: &Value, rhs: &Value) -> Option<(i64, i64)> {
+    match (lhs.int, rhs.int) {
+        (Some(lhs_int), Some(rhs_int)) => Some((lhs_int, rhs_int)),
+        (Some(lhs_int), None) => integral_float(rhs).map(|rhs_int| (lhs_int, rhs_int)),
+        (None, Some(rhs_int)) => integral_float(lhs).map(|lhs_int| (lhs_int, rhs_int)),
+        (None, None) => None,
+    }
+}
+/// Returns float of `value` as int if it has no fractional part and fits in int
+fn integral_float(value: &Value) -> Option<i64> {
+    let float = value.float?;
+    if float.fract() == 0.0 && (i64::MIN as f64) <= float && float < (i64::MAX as f64) {
+        Some(float as i64)
+    } else {
+        None
+    }
+}
+/// Returns both operands as float if one is bigint and the other is float with fractional part
+pub fn bigint_float_operands(lhs: &Value, rhs: &Value) -> Option<(f64, f64)> {
+    if let (Some(lhs_bigint), Some(rhs_float)) = (&lhs.bigint, rhs.float) {
+        lhs_bigint.to_f64().map(|lhs_float| (lhs_float, rhs_float))
+    } else if let (Some(lhs_float), Some(rhs_bigint)) = (lhs.float, &rhs.bigint) {
+        rhs_bigint.to_f64().map(|rhs_float| (lhs_float, rhs_float))
+    } else {
+        None
+    }
+}
+fn integer_return(int: i64) -> EvalReturn {
+    let mut retval = Value::new();
+    retval.int = Some(int);
+    retval.sil_type = SILType::Int;
+    EvalReturn {
+        result: EvalResult::Normal,
+        values: vec![retval],
+    }
+}
+fn to_bigint(value: &Value) -> Option<BigInt> {
+    if let Some(int) = value.int {
+        Some(BigInt::from(int))
+    } else if let Some(float) = value.float {
+        if float.fract() == 0.0 {
+            BigInt::from_f64(float)
+        } else {
+            None
+        }
+    } else {
+        None
+    }
 }
@@ -0,0 +1,107 @@
+use crate::silang::{
+    Interpreter,
+    Value,
+    EvalReturn,
+    EvalResult,
+    SILType,
+};
+
+impl Interpreter {
+    pub fn assert(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        let values = self.assert_arguments(args)?;
+        if values.is_empty() || 2 < values.len() {
+            return Err("assert: Argument length must be 1-2".to_owned())
+        }
+        match values[0].bool {
+            Some(true) => Ok(EvalReturn { result: EvalResult::Normal, values: vec![] }),
+            Some(false) => Err(format!("assert: Assertion failed{}", self.assert_message(values.get(1)))),
+            None => Err("assert: Argument 1 must be bool".to_owned()),
+        }
+    }
+
+    /// Fails with diff of formatted values unless they are equal by `equal_value`
+    pub fn assert_eq(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        let values = self.assert_arguments(args)?;
+        if values.len() < 2 || 3 < values.len() {
+            return Err("assert_eq: Argument length must be 2-3".to_owned())
+        }
+        if self.equal_value(&values[0], &values[1]) {
+            return Ok(EvalReturn { result: EvalResult::Normal, values: vec![] })
+        }
+        let left = self.format_value(&values[0]);
+        let right = self.format_value(&values[1]);
+        Err(format!("assert_eq: Values are not equal{}\n{}", self.assert_message(values.get(2)), diff(&left, &right)))
+    }
+
+    /// Evaluates argument 1 and returns its error message, or fails if it succeeds
+    pub fn assert_error(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        if args.len() < 2 || 3 < args.len() {
+            return Err("assert_error: Argument length must be 1-2".to_owned())
+        }
+        match self.eval_value(&args[1], true) {
+            Ok(_) => {
+                let message = match args.get(2) {
+                    Some(arg) => self.eval_value(arg, true)?.values,
+                    None => Vec::new(),
+                };
+                Err(format!("assert_error: Expected error{}", self.assert_message(message.first())))
+            },
+            Err(e) => {
+                let mut retval = Value::new();
+                retval.string = Some(e);
+                retval.sil_type = SILType::String;
+                Ok(EvalReturn { result: EvalResult::Normal, values: vec![retval] })
+            },
+        }
+    }
+
+    fn assert_arguments(&mut self, args: &[Value]) -> Result<Vec<Value>, String> {
+        let mut values = Vec::new();
+        for arg in &args[1..] {
+            values.append(&mut self.eval_value(arg, true)?.values);
+        }
+        Ok(values)
+    }
+    fn assert_message(&mut self, message: Option<&Value>) -> String {
+        match message {
+            Some(message) => format!(": {}", self.format_value(message)),
+            None => String::new(),
+        }
+    }
+}
+
+/// Returns line diff of `left` and `right` marking lines only in left by `-` and only in right by `+`
+fn diff(left: &str, right: &str) -> String {
+    let left: Vec<&str> = left.split('\n').collect();
+    let right: Vec<&str> = right.split('\n').collect();
+    if left.len() == 1 && right.len() == 1 {
+        return format!("-{}\n+{}", left[0], right[0])
+    }
+    // Lengths of longest common subsequences of suffixes
+    let mut lengths = vec![vec![0; right.len() + 1]; left.len() + 1];
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            lengths[i][j] = if left[i] == right[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() || j < right.len() {
+        if i < left.len() && j < right.len() && left[i] == right[j] {
+            lines.push(format!(" {}", left[i]));
+            i += 1;
+            j += 1;
+        } else if j == right.len() || (i < left.len() && lengths[i + 1][j] >= lengths[i][j + 1]) {
+            lines.push(format!("-{}", left[i]));
+            i += 1;
+        } else {
+            lines.push(format!("+{}", right[j]));
+            j += 1;
+        }
+    }
+    lines.join("\n")
+}
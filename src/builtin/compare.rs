@@ -7,11 +7,19 @@ use crate::silang::{
     ScopeType,
     SILType,
 };
+use super::arithmetic::{
+    bigint_operands,
+    bigint_float_operands,
+};
 
 impl Interpreter {
     pub fn equal_value(&self, lhs: &Value, rhs: &Value) -> bool {
         if lhs.string.is_some() && rhs.string.is_some() {
             lhs.string.as_ref().unwrap() == rhs.string.as_ref().unwrap()
+        } else if let Some((lhs_bigint, rhs_bigint)) = bigint_operands(lhs, rhs) {
+            lhs_bigint == rhs_bigint
+        } else if let Some((lhs_float, rhs_float)) = bigint_float_operands(lhs, rhs) {
+            lhs_float == rhs_float
         } else if let Some(lhs_int) = lhs.int {
             if let Some(rhs_int) = rhs.int {
                 lhs_int == rhs_int
@@ -28,6 +36,11 @@ impl Interpreter {
             } else {
                 false
             }
+        } else if let (Some(lhs_bool), Some(rhs_bool)) = (lhs.bool, rhs.bool) {
+            lhs_bool == rhs_bool
+        } else if let (Some(lhs_vector), Some(rhs_vector)) = (&lhs.vector, &rhs.vector) {
+            lhs_vector.len() == rhs_vector.len()
+                && lhs_vector.iter().zip(rhs_vector).all(|(l, r)| self.equal_value(l, r))
         } else {
             false
         }
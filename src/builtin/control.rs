@@ -28,19 +28,27 @@ impl Interpreter {
         if args[1].expression.is_none() {
             return Err("if: Argument 1 must be expression".to_owned())
         }
+        #[cfg(any(target_family = "unix", target_family = "windows"))]
+        let span = self.current_span;
         match self.eval_value(&args[1], true) {
             Ok(result) => {
                 if result.values.len() != 1 || result.values[0].bool.is_none() {
                     return Err("if: Argument 1 must be single bool value".to_owned())
                 }
+                #[cfg(any(target_family = "unix", target_family = "windows"))]
+                {
+                    if self.coverage.is_some() {
+                        self.cover_branch(span, !result.values[0].bool.unwrap());
+                    }
+                }
                 if result.values[0].bool.unwrap() {
-                    match self.eval_value(&args[2], true) {
+                    match self.eval_body(&args[2], ScopeType::If) {
                         Ok(result) => Ok(result),
                         Err(e) => return Err(e),
                     }
                 } else {
                     if args.len() == 4 {
-                        match self.eval_value(&args[3], true) {
+                        match self.eval_body(&args[3], ScopeType::If) {
                             Ok(result) => Ok(result),
                             Err(e) => return Err(e),
                         }
@@ -67,16 +75,20 @@ impl Interpreter {
             values: vec![],
         };
         loop {
+            self.step()?;
             match self.eval_value(&args[1], true) {
                 Ok(result) => {
                     if result.values.len() != 1 || result.values[0].bool.is_none() {
                         return Err("loop: Argument 1 must be single bool value".to_owned())
                     }
                     if result.values[0].bool.unwrap() {
-                        match self.eval_value(&args[2], true) {
+                        match self.eval_body(&args[2], ScopeType::Loop) {
                             Ok(result) => retval = result,
                             Err(e) => return Err(e),
                         }
+                        if self.exit_status.is_some() || retval.result == EvalResult::Return {
+                            break
+                        }
                     } else {
                         break
                     }
@@ -5,6 +5,7 @@ use crate::silang::{
     EvalResult,
     SILType,
 };
+use num_bigint::BigInt;
 
 impl Interpreter {
     pub fn decas(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
@@ -44,10 +45,14 @@ impl Interpreter {
                         v.string = Some("".to_owned());
                     } else if type_value == SILType::Int {
                         v.int = Some(0);
+                    } else if type_value == SILType::BigInt {
+                        v.bigint = Some(BigInt::from(0));
                     } else if type_value == SILType::Float {
                         v.float = Some(0.0);
                     } else if type_value == SILType::Bool {
                         v.bool = Some(false);
+                    } else if type_value == SILType::Vector {
+                        v.vector = Some(Vec::new());
                     }
                     v.sil_type = type_value;
                 }
@@ -166,7 +171,7 @@ impl Interpreter {
                     return Err("assign: LHS must be identifier".to_owned())
                 }
                 let current_scope = self.context.current_scope();
-                let mut store_value = rhs.clone();
+                let mut store_value = rhs_values[i].clone();
                 if self.context.is_untyped() {
                     store_value.sil_type = SILType::Any;
                 }
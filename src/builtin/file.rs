@@ -0,0 +1,117 @@
+use crate::silang::{
+    Interpreter,
+    Value,
+    EvalReturn,
+    EvalResult,
+    SILType,
+};
+use crate::define;
+
+use std::fs;
+use std::io::Write;
+
+impl Interpreter {
+    pub fn read_file(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        let paths = self.file_arguments(define::READ_FILE, args, 1)?;
+        match fs::read_to_string(&paths[0]) {
+            Ok(content) => Ok(values_return(vec![string_value(content)])),
+            Err(e) => Err(io_error(define::READ_FILE, &e)),
+        }
+    }
+    pub fn read_lines(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        let paths = self.file_arguments(define::READ_LINES, args, 1)?;
+        match fs::read_to_string(&paths[0]) {
+            Ok(content) => {
+                let mut lines = Value::new();
+                lines.vector = Some(content.lines().map(|line| string_value(line.to_owned())).collect());
+                lines.sil_type = SILType::Vector;
+                Ok(values_return(vec![lines]))
+            },
+            Err(e) => Err(io_error(define::READ_LINES, &e)),
+        }
+    }
+    pub fn write_file(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        let strings = self.file_arguments(define::WRITE_FILE, args, 2)?;
+        match fs::write(&strings[0], &strings[1]) {
+            Ok(_) => Ok(values_return(vec![])),
+            Err(e) => Err(io_error(define::WRITE_FILE, &e)),
+        }
+    }
+    pub fn append_file(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        let strings = self.file_arguments(define::APPEND_FILE, args, 2)?;
+        let result = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&strings[0])
+            .and_then(|mut file| file.write_all(strings[1].as_bytes()));
+        match result {
+            Ok(_) => Ok(values_return(vec![])),
+            Err(e) => Err(io_error(define::APPEND_FILE, &e)),
+        }
+    }
+    pub fn exists(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        let paths = self.file_arguments(define::EXISTS, args, 1)?;
+        let mut retval = Value::new();
+        retval.bool = Some(std::path::Path::new(&paths[0]).exists());
+        retval.sil_type = SILType::Bool;
+        Ok(values_return(vec![retval]))
+    }
+    pub fn remove_file(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        let paths = self.file_arguments(define::REMOVE_FILE, args, 1)?;
+        match fs::remove_file(&paths[0]) {
+            Ok(_) => Ok(values_return(vec![])),
+            Err(e) => Err(io_error(define::REMOVE_FILE, &e)),
+        }
+    }
+    pub fn list_dir(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        let paths = self.file_arguments(define::LIST_DIR, args, 1)?;
+        let entries = match fs::read_dir(&paths[0]) {
+            Ok(entries) => entries,
+            Err(e) => return Err(io_error(define::LIST_DIR, &e)),
+        };
+        let mut names = Vec::new();
+        for entry in entries {
+            match entry {
+                Ok(entry) => names.push(entry.file_name().to_string_lossy().into_owned()),
+                Err(e) => return Err(io_error(define::LIST_DIR, &e)),
+            }
+        }
+        names.sort();
+        let mut retval = Value::new();
+        retval.vector = Some(names.into_iter().map(string_value).collect());
+        retval.sil_type = SILType::Vector;
+        Ok(values_return(vec![retval]))
+    }
+    pub fn mkdir(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        let paths = self.file_arguments(define::MKDIR, args, 1)?;
+        match fs::create_dir_all(&paths[0]) {
+            Ok(_) => Ok(values_return(vec![])),
+            Err(e) => Err(io_error(define::MKDIR, &e)),
+        }
+    }
+
+    /// Evaluates `length` string arguments of file system function `name`
+    fn file_arguments(&mut self, name: &str, args: &[Value], length: usize) -> Result<Vec<String>, String> {
+        if !self.file_system_enabled {
+            return Err(format!("{}: {}", name, define::FILE_SYSTEM_DISABLED))
+        }
+        self.string_arguments(name, args, length)
+    }
+}
+
+/// Formats I/O error with its kind, e.g. `read_file: NotFound: No such file or directory`
+fn io_error(name: &str, error: &std::io::Error) -> String {
+    format!("{}: {:?}: {}", name, error.kind(), error)
+}
+fn string_value(string: String) -> Value {
+    let mut value = Value::new();
+    value.string = Some(string);
+    value.sil_type = SILType::String;
+    value
+}
+fn values_return(values: Vec<Value>) -> EvalReturn {
+    EvalReturn {
+        result: EvalResult::Normal,
+        values,
+    }
+}
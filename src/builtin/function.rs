@@ -11,6 +11,9 @@ impl Interpreter {
         let mut retval;
         let mut storeval = Value::new();
         let udf_args;
+        if args.len() < 4 || 5 < args.len() {
+            return Err("f: Argument length must be 3-4".to_owned())
+        }
         if let Some(expr) = &args[2].expression {
             udf_args = expr.clone();
         } else {
@@ -56,6 +59,7 @@ impl Interpreter {
                 scope: self.context.scope.clone(),
                 args: udf_args,
                 block: udf_block,
+                source: self.current_source,
             }
         );
         retval = args[1].clone();
@@ -116,6 +120,7 @@ impl Interpreter {
                 scope: self.context.scope.clone(),
                 args: args[1].expression.as_ref().unwrap().clone(),
                 block: args[2].block.as_ref().unwrap().clone(),
+                source: self.current_source,
             }
         );
         Ok(
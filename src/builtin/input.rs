@@ -0,0 +1,88 @@
+use crate::silang::{
+    Interpreter,
+    Value,
+    EvalReturn,
+    EvalResult,
+    SILType,
+};
+
+impl Interpreter {
+    /// Reads a line with `stdin_func` and remembers whether input reached EOF
+    pub fn read_stdin_line(&mut self) -> Option<String> {
+        let line = (self.stdin_func)(self);
+        if line.is_none() {
+            self.stdin_eof = true;
+        }
+        line
+    }
+
+    pub fn input(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        for v in &args[1..] {
+            match self.print_value(v) {
+                Ok(_) => {},
+                Err(e) => return Err(e),
+            }
+        }
+        let mut line = match self.read_stdin_line() {
+            Some(line) => line,
+            None => return Ok(eof_return()),
+        };
+        if line.ends_with('\n') {
+            line.pop();
+            if line.ends_with('\r') {
+                line.pop();
+            }
+        }
+        Ok(string_return(line))
+    }
+    pub fn read_line(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        if args.len() != 1 {
+            return Err("read_line: Argument length must be 0".to_owned())
+        }
+        match self.read_stdin_line() {
+            Some(line) => Ok(string_return(line)),
+            None => Ok(eof_return()),
+        }
+    }
+    pub fn read_all(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        if args.len() != 1 {
+            return Err("read_all: Argument length must be 0".to_owned())
+        }
+        let mut buffer = String::new();
+        while let Some(line) = self.read_stdin_line() {
+            buffer.push_str(&line);
+        }
+        Ok(string_return(buffer))
+    }
+    pub fn eof(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        if args.len() != 1 {
+            return Err("eof: Argument length must be 0".to_owned())
+        }
+        Ok(bool_return(self.stdin_eof))
+    }
+}
+
+fn bool_return(bool: bool) -> EvalReturn {
+    let mut retval = Value::new();
+    retval.bool = Some(bool);
+    retval.sil_type = SILType::Bool;
+    EvalReturn {
+        result: EvalResult::Normal,
+        values: vec![retval],
+    }
+}
+
+/// `input` and `read_line` return `false` instead of a string at EOF
+fn eof_return() -> EvalReturn {
+    bool_return(false)
+}
+
+fn string_return(string: String) -> EvalReturn {
+    let mut retval = Value::new();
+    retval.string = Some(string);
+    retval.sil_type = SILType::String;
+    EvalReturn {
+        result: EvalResult::Normal,
+        values: vec![retval],
+    }
+}
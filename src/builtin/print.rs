@@ -4,18 +4,25 @@ use crate::silang::{
     EvalReturn,
     EvalResult,
 };
+use crate::define;
+
+use std::cell::RefCell;
 
 impl Interpreter {
     pub fn print_value(&mut self, value: &Value) -> Result<Vec<Value>, String> {
+        self.write_value(value, self.stdout_func)
+    }
+    /// Writes `value` with `output` function and returns printed values
+    pub fn write_value(&mut self, value: &Value, output: fn (&mut Interpreter, &str)) -> Result<Vec<Value>, String> {
         let mut retval = Vec::new();
         if value.expression.is_some() {
             match self.eval_value(value, true) {
                 Ok(result) => {
                     if 1 < result.values.len() {
-                        (self.stdout_func)(self, "(");
+                        output(self, "(");
                     }
                     for i in 0..result.values.len() {
-                        match self.print_value(&result.values[i]) {
+                        match self.write_value(&result.values[i], output) {
                             Ok(values) => {
                                 for v in values {
                                     retval.push(v);
@@ -24,11 +31,11 @@ impl Interpreter {
                             Err(e) => return Err(e),
                         }
                         if i != result.values.len() - 1 {
-                            (self.stdout_func)(self, " ");
+                            output(self, " ");
                         }
                     }
                     if 1 < result.values.len() {
-                        (self.stdout_func)(self, ")");
+                        output(self, ")");
                     }
                 },
                 Err(e) => return Err(e),
@@ -36,7 +43,7 @@ impl Interpreter {
         } else if value.identifier_id.is_some() {
             match self.dereference_value(value) {
                 Ok(v) => {
-                    match self.print_value(&v) {
+                    match self.write_value(&v, output) {
                         Ok(values) => {
                             for v in values {
                                 retval.push(v);
@@ -48,20 +55,36 @@ impl Interpreter {
                 Err(e) => return Err(e),
             }
         } else if let Some(string) = &value.string {
-            (self.stdout_func)(self, &string);
+            output(self, &string);
             retval.push(value.clone());
         } else if let Some(int) = value.int {
-            (self.stdout_func)(self, &format!("{}", int));
+            output(self, &format!("{}", int));
+            retval.push(value.clone());
+        } else if let Some(bigint) = &value.bigint {
+            output(self, &format!("{}", bigint));
             retval.push(value.clone());
         } else if let Some(float) = value.float {
-            (self.stdout_func)(self, &format!("{}", float));
+            output(self, &format!("{}", float));
             retval.push(value.clone());
         } else if let Some(bool_val) = value.bool {
             if bool_val {
-                (self.stdout_func)(self, "true");
+                output(self, "true");
             } else {
-                (self.stdout_func)(self, "false");
+                output(self, "false");
+            }
+            retval.push(value.clone());
+        } else if let Some(vector) = &value.vector {
+            output(self, "[");
+            for i in 0..vector.len() {
+                match self.write_value(&vector[i], output) {
+                    Ok(_) => {},
+                    Err(e) => return Err(e),
+                }
+                if i != vector.len() - 1 {
+                    output(self, " ");
+                }
             }
+            output(self, "]");
             retval.push(value.clone());
         } else {
             return Err("print: undefined value".to_owned())
@@ -69,28 +92,21 @@ impl Interpreter {
         Ok(retval)
     }
     pub fn print(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
-        let mut retval = Vec::new();
-        for v in &args[1..] {
-            match self.print_value(v) {
-                Ok(values) => {
-                    for v in values {
-                        retval.push(v);
-                    }
-                },
-                Err(e) => return Err(e),
-            }
-        }
-        Ok(
-            EvalReturn {
-                result: EvalResult::Normal,
-                values: retval,
-            }
-        )
+        self.write(args, self.stdout_func, false)
     }
     pub fn println(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        self.write(args, self.stdout_func, true)
+    }
+    pub fn eprint(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        self.write(args, self.stderr_func, false)
+    }
+    pub fn eprintln(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        self.write(args, self.stderr_func, true)
+    }
+    fn write(&mut self, args: &[Value], output: fn (&mut Interpreter, &str), newline: bool) -> Result<EvalReturn, String> {
         let mut retval = Vec::new();
         for v in &args[1..] {
-            match self.print_value(v) {
+            match self.write_value(v, output) {
                 Ok(values) => {
                     for v in values {
                         retval.push(v);
@@ -99,7 +115,9 @@ impl Interpreter {
                 Err(e) => return Err(e),
             }
         }
-        (self.stdout_func)(self, "\n");
+        if newline {
+            output(self, "\n");
+        }
         Ok(
             EvalReturn {
                 result: EvalResult::Normal,
@@ -107,4 +125,22 @@ impl Interpreter {
             }
         )
     }
+
+    /// Formats `value` like `print` does
+    pub fn format_value(&mut self, value: &Value) -> String {
+        if value.user_defined_function.is_some() || value.function.is_some() {
+            return define::FUNCTION.to_owned()
+        }
+        let result = self.write_value(value, |_, data| FORMAT_OUTPUT.with(|buffer| buffer.borrow_mut().push_str(data)));
+        let output = FORMAT_OUTPUT.with(|buffer| buffer.replace(String::new()));
+        match result {
+            Ok(_) => output,
+            Err(_) => define::VOID.to_owned(),
+        }
+    }
+}
+
+thread_local! {
+    /// Output of `write_value` formatting values
+    static FORMAT_OUTPUT: RefCell<String> = const { RefCell::new(String::new()) };
 }
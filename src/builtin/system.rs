@@ -0,0 +1,112 @@
+use crate::silang::{
+    Interpreter,
+    Value,
+    EvalReturn,
+    EvalResult,
+    SILType,
+};
+use crate::define;
+
+use std::convert::TryFrom;
+
+impl Interpreter {
+    /// Stores command line arguments as `args` vector
+    pub fn set_args(&mut self, args: &[String]) {
+        let mut vector = Vec::new();
+        for arg in args {
+            let mut value = Value::new();
+            value.string = Some(arg.clone());
+            value.sil_type = SILType::String;
+            vector.push(value);
+        }
+        let mut value = Value::new();
+        value.identifier = Some(define::ARGS.to_owned());
+        value.vector = Some(vector);
+        value.sil_type = SILType::Vector;
+        match self.context.search_identifier_id(define::ARGS) {
+            Some((0, id)) => self.context.set_value_from_identifier_id(id, value),
+            _ => {
+                self.context.store_identifier(0, define::ARGS, value);
+            },
+        }
+    }
+
+    #[cfg(any(target_family = "unix", target_family = "windows"))]
+    pub fn env(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        let names = self.string_arguments(define::ENV, args, 1)?;
+        let mut retval = Value::new();
+        retval.string = Some(std::env::var(&names[0]).unwrap_or_default());
+        retval.sil_type = SILType::String;
+        Ok(
+            EvalReturn {
+                result: EvalResult::Normal,
+                values: vec![retval],
+            }
+        )
+    }
+    #[cfg(any(target_family = "unix", target_family = "windows"))]
+    pub fn set_env(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        let strings = self.string_arguments(define::SET_ENV, args, 2)?;
+        if strings[0].is_empty() || strings[0].contains('=') || strings[0].contains('\0') || strings[1].contains('\0') {
+            return Err("set_env: Invalid environment variable".to_owned())
+        }
+        std::env::set_var(&strings[0], &strings[1]);
+        Ok(
+            EvalReturn {
+                result: EvalResult::Normal,
+                values: vec![],
+            }
+        )
+    }
+
+    pub fn exit(&mut self, args: &[Value]) -> Result<EvalReturn, String> {
+        let mut values = Vec::new();
+        for arg in &args[1..] {
+            match self.eval_value(arg, true) {
+                Ok(result) => {
+                    for v in result.values {
+                        values.push(v);
+                    }
+                },
+                Err(e) => return Err(e),
+            }
+        }
+        let status = match values.len() {
+            0 => 0,
+            1 => self.integer_operand(define::EXIT, &values[0])?,
+            _ => return Err("exit: Argument length must be 0-1".to_owned()),
+        };
+        match i32::try_from(status) {
+            Ok(status) => self.exit_status = Some(status),
+            Err(_) => return Err(format!("{}: {}", define::EXIT, define::INVALID_EXIT_STATUS)),
+        }
+        Ok(
+            EvalReturn {
+                result: EvalResult::Normal,
+                values: vec![],
+            }
+        )
+    }
+
+    /// Evaluates arguments of function `name` and checks they are `length` strings
+    pub fn string_arguments(&mut self, name: &str, args: &[Value], length: usize) -> Result<Vec<String>, String> {
+        let mut strings = Vec::new();
+        for arg in &args[1..] {
+            match self.eval_value(arg, true) {
+                Ok(result) => {
+                    for v in result.values {
+                        match v.string {
+                            Some(string) => strings.push(string),
+                            None => return Err(format!("{}: Argument must be string", name)),
+                        }
+                    }
+                },
+                Err(e) => return Err(e),
+            }
+        }
+        if strings.len() != length {
+            return Err(format!("{}: Argument length must be {}", name, length))
+        }
+        Ok(strings)
+    }
+}
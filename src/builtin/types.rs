@@ -4,6 +4,11 @@ use crate::silang::{
     SILType,
 };
 use crate::define;
+use num_bigint::BigInt;
+use num_traits::{
+    ToPrimitive,
+    FromPrimitive,
+};
 
 impl Interpreter {
     pub fn get_type_from_identifier(&self, identifier: &Value) -> Result<SILType, String> {
@@ -12,10 +17,14 @@ impl Interpreter {
                 Ok(SILType::String)
             } else if type_name == define::INT {
                 Ok(SILType::Int)
+            } else if type_name == define::BIGINT {
+                Ok(SILType::BigInt)
             } else if type_name == define::FLOAT {
                 Ok(SILType::Float)
             } else if type_name == define::BOOL {
                 Ok(SILType::Bool)
+            } else if type_name == define::VECTOR {
+                Ok(SILType::Vector)
             } else if type_name == define::VOID {
                 Ok(SILType::Void)
             } else {
@@ -39,6 +48,16 @@ impl Interpreter {
                     }
                     Err(_) => return Err("Unable to cast".to_owned()),
                 }
+            } else if to == SILType::BigInt {
+                match string.parse::<BigInt>() {
+                    Ok(num) => {
+                        let mut v = Value::new();
+                        v.bigint = Some(num);
+                        v.sil_type = SILType::BigInt;
+                        Ok(v)
+                    }
+                    Err(_) => Err("Unable to cast".to_owned()),
+                }
             } else if to == SILType::Float {
                 match string.parse() {
                     Ok(num) => {
@@ -62,11 +81,39 @@ impl Interpreter {
                 let mut v = Value::new();
                 v.float = Some(int as f64);
                 Ok(v)
+            } else if to == SILType::BigInt {
+                let mut v = Value::new();
+                v.bigint = Some(BigInt::from(int));
+                v.sil_type = SILType::BigInt;
+                Ok(v)
             } else if to == SILType::Int {
                 Ok(value.clone())
             } else {
                 Err("Unable to cast".to_owned())
             }
+        } else if let Some(bigint) = &value.bigint {
+            if to == SILType::String {
+                let mut v = Value::new();
+                v.string = Some(bigint.to_string());
+                Ok(v)
+            } else if to == SILType::Int {
+                match bigint.to_i64() {
+                    Some(int) => {
+                        let mut v = Value::new();
+                        v.int = Some(int);
+                        Ok(v)
+                    },
+                    None => Err(format!("Unable to cast: {}", define::INTEGER_OVERFLOW)),
+                }
+            } else if to == SILType::Float {
+                let mut v = Value::new();
+                v.float = bigint.to_f64();
+                Ok(v)
+            } else if to == SILType::BigInt {
+                Ok(value.clone())
+            } else {
+                Err("Unable to cast".to_owned())
+            }
         } else if let Some(float) = value.float {
             if to == SILType::String {
                 let mut v = Value::new();
@@ -76,6 +123,16 @@ impl Interpreter {
                 let mut v = Value::new();
                 v.int = Some(float as i64);
                 Ok(v)
+            } else if to == SILType::BigInt {
+                match BigInt::from_f64(float) {
+                    Some(bigint) => {
+                        let mut v = Value::new();
+                        v.bigint = Some(bigint);
+                        v.sil_type = SILType::BigInt;
+                        Ok(v)
+                    },
+                    None => Err("Unable to cast".to_owned()),
+                }
             } else if to == SILType::Float {
                 Ok(value.clone())
             } else {
@@ -0,0 +1,135 @@
+use crate::silang::Interpreter;
+use crate::parser::{
+    self,
+    Program,
+    Expression,
+    Span,
+};
+use crate::define;
+
+use std::collections::{
+    BTreeMap,
+    HashMap,
+};
+
+/// Statements and `if` branches executed while running
+#[derive(Default)]
+pub struct Coverage {
+    /// Hits keyed by source file index and span of statement
+    pub statements: HashMap<(usize, usize), u64>,
+    /// Hits of then and else branches keyed by source file index and span of `if`
+    pub branches: HashMap<(usize, usize), [u64; 2]>,
+}
+impl Coverage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Interpreter {
+    pub fn cover_statement(&mut self, statement: &parser::Statement) {
+        let source = match self.current_source {
+            Some(source) => source,
+            None => return,
+        };
+        if let Some(coverage) = &mut self.coverage {
+            *coverage.statements.entry((source, statement.span.rest)).or_default() += 1;
+        }
+    }
+    /// Records branch taken by `if` at `span`
+    pub fn cover_branch(&mut self, span: Span, else_branch: bool) {
+        let source = match self.current_source {
+            Some(source) => source,
+            None => return,
+        };
+        if let Some(coverage) = &mut self.coverage {
+            coverage.branches.entry((source, span.rest)).or_default()[else_branch as usize] += 1;
+        }
+    }
+
+    /// Returns coverage of every loaded source file in lcov format
+    ///
+    /// Files loaded more than once are merged into one record.
+    pub fn coverage_lcov(&self, coverage: &Coverage) -> String {
+        let mut files: Vec<(&str, Vec<usize>)> = Vec::new();
+        for (index, file) in self.source_files.iter().enumerate() {
+            match files.iter_mut().find(|(name, _)| *name == file.name) {
+                Some((_, indices)) => indices.push(index),
+                None => files.push((&file.name, vec![index])),
+            }
+        }
+        let mut buffer = String::from("TN:\n");
+        for (name, indices) in files {
+            let source = &self.source_files[indices[0]].source;
+            let program = match parser::program_all_consuming(source) {
+                Ok(program) => program.1,
+                Err(_) => continue,
+            };
+            let mut statements = Vec::new();
+            let mut ifs = Vec::new();
+            collect_program(&program, &mut statements, &mut ifs);
+
+            buffer.push_str(&format!("SF:{}\n", name));
+            let mut branches_hit = 0;
+            for (block, span) in ifs.iter().enumerate() {
+                let (line, _) = span.line_column(source);
+                let mut hits: Option<[u64; 2]> = None;
+                for index in &indices {
+                    if let Some(h) = coverage.branches.get(&(*index, span.rest)) {
+                        let total = hits.get_or_insert([0, 0]);
+                        total[0] += h[0];
+                        total[1] += h[1];
+                    }
+                }
+                for branch in 0..2 {
+                    let taken = match hits {
+                        Some(hits) => {
+                            if 0 < hits[branch] {
+                                branches_hit += 1;
+                            }
+                            hits[branch].to_string()
+                        },
+                        None => "-".to_owned(),
+                    };
+                    buffer.push_str(&format!("BRDA:{},{},{},{}\n", line, block, branch, taken));
+                }
+            }
+            buffer.push_str(&format!("BRF:{}\nBRH:{}\n", ifs.len() * 2, branches_hit));
+
+            let mut lines: BTreeMap<usize, u64> = BTreeMap::new();
+            for span in statements {
+                let (line, _) = span.line_column(source);
+                let hits: u64 = indices.iter().filter_map(|index| coverage.statements.get(&(*index, span.rest))).sum();
+                *lines.entry(line).or_default() += hits;
+            }
+            for (line, hits) in &lines {
+                buffer.push_str(&format!("DA:{},{}\n", line, hits));
+            }
+            let lines_hit = lines.values().filter(|hits| 0 < **hits).count();
+            buffer.push_str(&format!("LF:{}\nLH:{}\nend_of_record\n", lines.len(), lines_hit));
+        }
+        buffer
+    }
+}
+
+/// Collects spans of statements and `if` expressions in `program`
+fn collect_program(program: &Program, statements: &mut Vec<Span>, ifs: &mut Vec<Span>) {
+    for statement in &program.statements {
+        statements.push(statement.span);
+        collect_expression(&statement.expression, statements, ifs);
+    }
+}
+fn collect_expression(expression: &Expression, statements: &mut Vec<Span>, ifs: &mut Vec<Span>) {
+    if let Some(head) = expression.factors.first() {
+        if head.identifier.as_deref() == Some(define::IF) && head.expression.is_none() {
+            ifs.push(head.span);
+        }
+    }
+    for factor in &expression.factors {
+        if let Some(expression) = &factor.expression {
+            collect_expression(expression, statements, ifs);
+        } else if let Some(block) = &factor.block {
+            collect_program(&block.program, statements, ifs);
+        }
+    }
+}
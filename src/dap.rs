@@ -0,0 +1,399 @@
+use crate::silang::{
+    Interpreter,
+    Value,
+    ScopeType,
+};
+use crate::debugger::{
+    Debugger,
+    Breakpoint,
+    StepMode,
+};
+use crate::lsp::{
+    read_message,
+    write_message,
+};
+use crate::parser;
+use crate::preprocessor;
+use crate::typecheck;
+use crate::define;
+
+use std::cell::Cell;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde_json::{
+    json,
+    Value as Json,
+};
+
+static THREAD_ID: i64 = 1;
+static PROGRAM_FRAME_NAME: &str = "<program>";
+
+thread_local! {
+    /// Sequence number of the last message sent
+    static SEQUENCE: Cell<i64> = const { Cell::new(0) };
+    /// Reports the next stop as entry
+    static ENTRY: Cell<bool> = const { Cell::new(false) };
+    /// Set when the client disconnected while program was paused
+    static DISCONNECTED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Expandable item of `variables` request
+enum Reference {
+    Scope(usize),
+    Value(Box<Value>),
+}
+
+struct Launch {
+    program: parser::Program,
+    stop_on_entry: bool,
+    no_debug: bool,
+}
+
+fn send(mut message: Json) {
+    message["seq"] = json!(SEQUENCE.with(|seq| {
+        seq.set(seq.get() + 1);
+        seq.get()
+    }));
+    let stdout = io::stdout();
+    if let Err(e) = write_message(&mut stdout.lock(), &message) {
+        eprintln!("{}", e);
+    }
+}
+fn event(name: &str, body: Json) {
+    send(json!({ "type": "event", "event": name, "body": body }));
+}
+fn respond(request: &Json, result: Result<Json, String>) {
+    let mut response = json!({
+        "type": "response",
+        "request_seq": request["seq"],
+        "command": request["command"],
+        "success": result.is_ok(),
+    });
+    match result {
+        Ok(body) => response["body"] = body,
+        Err(message) => response["message"] = json!(message),
+    }
+    send(response);
+}
+fn read_request() -> Option<Json> {
+    let stdin = io::stdin();
+    match read_message(&mut stdin.lock()) {
+        Ok(request) => request,
+        Err(e) => {
+            eprintln!("{}", e);
+            None
+        },
+    }
+}
+
+fn capabilities() -> Json {
+    json!({
+        "supportsConfigurationDoneRequest": true,
+        "supportsEvaluateForHovers": true,
+    })
+}
+fn threads() -> Json {
+    json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] })
+}
+
+/// Replaces breakpoints in the source file of `arguments`
+fn set_breakpoints(breakpoints: &mut Vec<Breakpoint>, arguments: &Json) -> Result<Json, String> {
+    let path = match arguments["source"]["path"].as_str() {
+        Some(path) => path.to_owned(),
+        None => return Err("setBreakpoints: source.path is required".to_owned()),
+    };
+    breakpoints.retain(|b| b.file.as_ref() != Some(&path));
+    let mut verified = Vec::new();
+    for requested in arguments["breakpoints"].as_array().into_iter().flatten() {
+        if let Some(line) = requested["line"].as_u64() {
+            breakpoints.push(Breakpoint { file: Some(path.clone()), line: line as usize });
+            verified.push(json!({ "verified": true, "line": line }));
+        }
+    }
+    Ok(json!({ "breakpoints": verified }))
+}
+
+/// Reads, preprocesses and parses the program of `launch` request
+fn launch(interpreter: &mut Interpreter, arguments: &Json) -> Result<Launch, String> {
+    let file = match arguments["program"].as_str() {
+        Some(file) => file,
+        None => return Err("launch: program is required".to_owned()),
+    };
+    let mut buffer = match fs::read_to_string(file) {
+        Ok(buffer) => buffer,
+        Err(e) => return Err(format!("{}: {}", file, e)),
+    };
+    buffer.push('\n');
+    let source_code = match preprocessor::preprocess(&buffer) {
+        Ok(source_code) => source_code,
+        Err(e) => return Err(format!("Preprocess error\n{}", e)),
+    };
+    let program = match parser::program_all_consuming(&source_code) {
+        Ok(program) => program.1,
+        Err(nom::Err::Error(e)) => return Err(format!("Parse error\n{}", nom::error::convert_error(source_code.as_str(), e))),
+        Err(_) => return Err("Parse error".to_owned()),
+    };
+    let args: Vec<String> = arguments["args"].as_array().into_iter().flatten()
+        .filter_map(|arg| arg.as_str().map(|s| s.to_owned()))
+        .collect();
+    interpreter.set_args(&args);
+    interpreter.current_source = Some(interpreter.add_source_file(file, &source_code));
+    Ok(Launch {
+        program,
+        stop_on_entry: arguments["stopOnEntry"].as_bool().unwrap_or(false),
+        no_debug: arguments["noDebug"].as_bool().unwrap_or(false),
+    })
+}
+
+/// Runs launched program and returns exit status
+fn execute(interpreter: &mut Interpreter, launch: Launch, breakpoints: Vec<Breakpoint>) -> i32 {
+    if !launch.no_debug {
+        let mut debugger = Debugger::new(breakpoints);
+        debugger.mode = if launch.stop_on_entry { StepMode::Step } else { StepMode::Continue };
+        debugger.pause_func = pause;
+        ENTRY.with(|entry| entry.set(launch.stop_on_entry));
+        interpreter.debugger = Some(debugger);
+    }
+    let result = interpreter.run(&launch.program);
+    interpreter.debugger = None;
+    if let Some(status) = interpreter.exit_status {
+        return status
+    }
+    match result {
+        Ok(_) => 0,
+        Err(e) => {
+            event("output", json!({ "category": "stderr", "output": format!("{}\n", e) }));
+            define::EXIT_RUNTIME_ERROR
+        },
+    }
+}
+
+/// Reports stop to the client and handles requests until running is resumed
+fn pause(interpreter: &mut Interpreter, debugger: &mut Debugger, file: &str, line: usize) -> bool {
+    let reason = if ENTRY.with(|entry| entry.replace(false)) {
+        "entry"
+    } else if debugger.breakpoint_at(file, line) {
+        "breakpoint"
+    } else {
+        "step"
+    };
+    event("stopped", json!({ "reason": reason, "threadId": THREAD_ID, "allThreadsStopped": true }));
+    let depth = interpreter.call_stack.len();
+    let mut references = Vec::new();
+    loop {
+        let request = match read_request() {
+            Some(request) => request,
+            // Run to the end without debugger if the client is gone
+            None => return false,
+        };
+        let arguments = &request["arguments"];
+        let mode = match request["command"].as_str().unwrap_or("") {
+            "continue" => StepMode::Continue,
+            "next" => StepMode::Next(depth),
+            "stepIn" => StepMode::Step,
+            "stepOut" => StepMode::Finish(depth),
+            "disconnect" => {
+                respond(&request, Ok(json!({})));
+                DISCONNECTED.with(|disconnected| disconnected.set(true));
+                interpreter.exit_status = Some(0);
+                return false
+            },
+            command => {
+                let result = match command {
+                    "threads" => Ok(threads()),
+                    "stackTrace" => Ok(interpreter.dap_stack_trace(debugger)),
+                    "scopes" => interpreter.dap_scopes(debugger, arguments, &mut references),
+                    "variables" => interpreter.dap_variables(arguments, &mut references),
+                    "evaluate" => interpreter.dap_evaluate(debugger, arguments, &mut references),
+                    "setBreakpoints" => set_breakpoints(&mut debugger.breakpoints, arguments),
+                    "pause" => Ok(json!({})),
+                    _ => Err(format!("Unsupported request: {}", command)),
+                };
+                respond(&request, result);
+                continue;
+            },
+        };
+        debugger.mode = mode;
+        respond(&request, Ok(json!({ "allThreadsContinued": true })));
+        return true
+    }
+}
+
+/// Returns name of DAP scope of `scope_type`
+fn scope_name(scope_type: &ScopeType) -> &'static str {
+    match scope_type {
+        ScopeType::Root => "Builtins",
+        ScopeType::Program => "Globals",
+        ScopeType::Block => "Block",
+        ScopeType::UserDefinedFunction => "Function",
+        ScopeType::If => "If",
+        ScopeType::Loop => "Loop",
+        ScopeType::UnTyped => "Untyped",
+    }
+}
+
+/// Returns variables reference of `value` which is 0 unless it is expandable
+fn reference(value: &Value, references: &mut Vec<Reference>) -> usize {
+    let expandable = value.vector.as_ref().is_some_and(|v| !v.is_empty())
+        || value.map.as_ref().is_some_and(|m| !m.is_empty());
+    if !expandable {
+        return 0
+    }
+    references.push(Reference::Value(Box::new(value.clone())));
+    references.len()
+}
+
+impl Interpreter {
+    fn dap_stack_trace(&self, debugger: &Debugger) -> Json {
+        let mut frames = Vec::new();
+        for (i, frame) in debugger.frames.iter().enumerate().rev() {
+            let path = self.frame_file(frame);
+            let name = Path::new(path).file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            frames.push(json!({
+                "id": i + 1,
+                "name": if frame.name.is_empty() { PROGRAM_FRAME_NAME } else { &frame.name },
+                "source": { "name": name, "path": path },
+                "line": frame.line,
+                "column": 1,
+            }));
+        }
+        json!({ "stackFrames": frames, "totalFrames": debugger.frames.len() })
+    }
+
+    /// Returns scopes of the frame innermost first without builtins
+    fn dap_scopes(&self, debugger: &Debugger, arguments: &Json, references: &mut Vec<Reference>) -> Result<Json, String> {
+        let frame = match arguments["frameId"].as_u64().and_then(|id| debugger.frames.get((id as usize).wrapping_sub(1))) {
+            Some(frame) => frame,
+            None => return Err("scopes: Invalid frameId".to_owned()),
+        };
+        let mut scopes = Vec::new();
+        for scope in frame.scope.iter().rev() {
+            if scope.scope_type == ScopeType::Root {
+                continue;
+            }
+            references.push(Reference::Scope(scope.scope_number));
+            let mut dap_scope = json!({
+                "name": scope_name(&scope.scope_type),
+                "variablesReference": references.len(),
+                "expensive": false,
+            });
+            if scope.scope_type == ScopeType::UserDefinedFunction {
+                dap_scope["presentationHint"] = json!("locals");
+            }
+            scopes.push(dap_scope);
+        }
+        Ok(json!({ "scopes": scopes }))
+    }
+
+    /// Returns identifiers of a scope, or items of a vector or map sorted by key
+    fn dap_variables(&mut self, arguments: &Json, references: &mut Vec<Reference>) -> Result<Json, String> {
+        let children: Vec<(String, Value)> = match arguments["variablesReference"].as_u64().and_then(|id| references.get((id as usize).wrapping_sub(1))) {
+            Some(Reference::Scope(scope_number)) => {
+                let mut names: Vec<_> = self.context.identifier_index.get(*scope_number).into_iter().flatten()
+                    .map(|(name, id)| (name.clone(), *id))
+                    .collect();
+                names.sort();
+                names.into_iter().map(|(name, id)| (name, self.context.get_value_from_identifier_id(id).clone())).collect()
+            },
+            Some(Reference::Value(value)) => {
+                if let Some(vector) = &value.vector {
+                    vector.iter().enumerate().map(|(i, v)| (format!("[{}]", i), v.clone())).collect()
+                } else if let Some(map) = &value.map {
+                    let mut items: Vec<_> = map.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+                    items.sort_by(|a, b| a.0.cmp(&b.0));
+                    items
+                } else {
+                    Vec::new()
+                }
+            },
+            None => return Err("variables: Invalid variablesReference".to_owned()),
+        };
+        let mut variables = Vec::new();
+        for (name, value) in children {
+            variables.push(json!({
+                "name": name,
+                "value": self.format_value(&value),
+                "type": typecheck::type_name(&value.sil_type),
+                "variablesReference": reference(&value, references),
+            }));
+        }
+        Ok(json!({ "variables": variables }))
+    }
+
+    /// Evaluates expression in scope of the frame, or the current statement
+    fn dap_evaluate(&mut self, debugger: &Debugger, arguments: &Json, references: &mut Vec<Reference>) -> Result<Json, String> {
+        let expression = arguments["expression"].as_str().unwrap_or("");
+        let expr = match parser::expression_all_consuming(expression) {
+            Ok(expr) => self.parser_expr_to_run_expr(&expr.1),
+            Err(_) => return Err("Parse error".to_owned()),
+        };
+        let frame_scope = arguments["frameId"].as_u64()
+            .and_then(|id| debugger.frames.get((id as usize).wrapping_sub(1)))
+            .map(|frame| frame.scope.clone());
+        let scope = frame_scope.map(|scope| std::mem::replace(&mut self.context.scope, scope));
+        let result = self.eval(&expr, true);
+        if let Some(scope) = scope {
+            self.context.scope = scope;
+        }
+        let values = result?.values;
+        let text: Vec<String> = values.iter().map(|v| self.format_value(v)).collect();
+        let variables_reference = match values.as_slice() {
+            [value] => reference(value, references),
+            _ => 0,
+        };
+        Ok(json!({ "result": text.join(" "), "variablesReference": variables_reference }))
+    }
+}
+
+/// Serves DAP over stdio until disconnect and returns exit status
+///
+/// The program runs on the calling thread, so requests are read
+/// only before the program starts, while paused and after it ends.
+/// Stdin carries the protocol, so the program reads it as EOF.
+pub fn run() -> i32 {
+    let mut interpreter = Interpreter::new();
+    interpreter.stdout_func = |_, data| event("output", json!({ "category": "stdout", "output": data }));
+    interpreter.stderr_func = |_, data| event("output", json!({ "category": "stderr", "output": data }));
+    interpreter.stdin_func = |_| None;
+    let mut breakpoints = Vec::new();
+    let mut launched = None;
+    loop {
+        let request = match read_request() {
+            Some(request) => request,
+            None => return 1,
+        };
+        match request["command"].as_str().unwrap_or("") {
+            "initialize" => {
+                respond(&request, Ok(capabilities()));
+                event("initialized", json!({}));
+            },
+            "launch" => match launch(&mut interpreter, &request["arguments"]) {
+                Ok(launch) => {
+                    launched = Some(launch);
+                    respond(&request, Ok(json!({})));
+                },
+                Err(e) => respond(&request, Err(e)),
+            },
+            "setBreakpoints" => respond(&request, set_breakpoints(&mut breakpoints, &request["arguments"])),
+            "configurationDone" => {
+                respond(&request, Ok(json!({})));
+                if let Some(launch) = launched.take() {
+                    let status = execute(&mut interpreter, launch, breakpoints.clone());
+                    if DISCONNECTED.with(|disconnected| disconnected.get()) {
+                        return 0
+                    }
+                    event("exited", json!({ "exitCode": status }));
+                    event("terminated", json!({}));
+                }
+            },
+            "threads" => respond(&request, Ok(threads())),
+            "disconnect" => {
+                respond(&request, Ok(json!({})));
+                return 0
+            },
+            command => respond(&request, Err(format!("Unsupported request: {}", command))),
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use crate::silang::{
+    Interpreter,
+    ScopeInfo,
+    SourceFile,
+};
+use crate::parser;
+
+use std::io::{
+    self,
+    Write,
+};
+use std::path::Path;
+
+static PROMPT: &str = "(sdb) ";
+static HELP: &str = "\
+s, step             Run until next statement
+n, next             Run until next statement in current function
+f, finish           Run until current function returns
+c, continue         Run until next breakpoint
+b, break [FILE:]LINE
+                    Set breakpoint
+d, delete [FILE:]LINE
+                    Delete breakpoint
+B, breakpoints      List breakpoints
+bt, backtrace       Show call stack
+l, list             Show source around current statement
+v, vars             List variables in scope chain
+p, print EXPR       Evaluate EXPR in current scope
+q, quit             Stop running
+h, help             Show this help";
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum StepMode {
+    Continue,
+    Step,
+    /// Stops at statement of call depth less than or equal to
+    Next(usize),
+    /// Stops at statement of call depth less than
+    Finish(usize),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Breakpoint {
+    /// Breaks in any file if None
+    pub file: Option<String>,
+    pub line: usize,
+}
+impl Breakpoint {
+    /// Parses `LINE` or `FILE:LINE`
+    pub fn parse(s: &str) -> Result<Breakpoint, String> {
+        let (file, line) = match s.rfind(':') {
+            Some(index) => (Some(s[..index].to_owned()), &s[index + 1..]),
+            None => (None, s),
+        };
+        match line.trim().parse() {
+            Ok(line) => Ok(Breakpoint { file, line }),
+            Err(_) => Err(format!("Invalid breakpoint: {}", s)),
+        }
+    }
+    /// Files match if either path ends with the other
+    fn matches(&self, file: &str, line: usize) -> bool {
+        self.line == line && self.file.as_ref().is_none_or(|f| Path::new(f).ends_with(file) || Path::new(file).ends_with(f))
+    }
+}
+
+/// Statement being executed at a call depth
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub name: String,
+    pub source: Option<usize>,
+    pub line: usize,
+    pub scope: Vec<ScopeInfo>,
+}
+
+/// Called when debugger stops at `FILE` `LINE`, returns false to stop debugging
+pub type PauseFunc = fn (&mut Interpreter, &mut Debugger, &str, usize) -> bool;
+
+pub struct Debugger {
+    pub breakpoints: Vec<Breakpoint>,
+    pub mode: StepMode,
+    /// Outermost first
+    pub frames: Vec<Frame>,
+    pub pause_func: PauseFunc,
+}
+impl Debugger {
+    /// Returns terminal debugger which stops at the first statement unless breakpoints are given
+    pub fn new(breakpoints: Vec<Breakpoint>) -> Self {
+        let mode = if breakpoints.is_empty() { StepMode::Step } else { StepMode::Continue };
+        Debugger { breakpoints, mode, frames: Vec::new(), pause_func: Interpreter::debug_prompt }
+    }
+    pub fn breakpoint_at(&self, file: &str, line: usize) -> bool {
+        self.breakpoints.iter().any(|b| b.matches(file, line))
+    }
+    fn should_stop(&self, file: &str, line: usize, depth: usize) -> bool {
+        let stepped = match self.mode {
+            StepMode::Continue => false,
+            StepMode::Step => true,
+            StepMode::Next(d) => depth <= d,
+            StepMode::Finish(d) => depth < d,
+        };
+        stepped || self.breakpoint_at(file, line)
+    }
+}
+
+impl Interpreter {
+    /// Pauses before `statement` if debugger should stop there and reads commands
+    pub fn debug_statement(&mut self, statement: &parser::Statement) {
+        let (file, line) = match self.statement_position(statement) {
+            Some((file, line)) => (file.to_owned(), line),
+            None => return,
+        };
+        let mut debugger = match self.debugger.take() {
+            Some(debugger) => debugger,
+            None => return,
+        };
+        let depth = self.call_stack.len();
+        debugger.frames.truncate(depth);
+        debugger.frames.push(Frame {
+            name: self.call_stack.last().cloned().unwrap_or_default(),
+            source: self.current_source,
+            line,
+            scope: self.context.scope.clone(),
+        });
+        if debugger.should_stop(&file, line, depth) && !(debugger.pause_func)(self, &mut debugger, &file, line) {
+            return
+        }
+        self.debugger = Some(debugger);
+    }
+
+    /// Reads debugger commands from stdin until running is resumed
+    fn debug_prompt(&mut self, debugger: &mut Debugger, file: &str, line: usize) -> bool {
+        eprintln!("{}:{}: {}", file, line, self.source_line(line).trim());
+        loop {
+            eprint!("{}", PROMPT);
+            io::stderr().flush().ok();
+            let mut input = String::new();
+            match io::stdin().read_line(&mut input) {
+                Ok(0) | Err(_) => {
+                    // Run to the end without debugger on EOF
+                    return false
+                },
+                Ok(_) => {},
+            }
+            let input = input.trim();
+            let (command, argument) = match input.find(char::is_whitespace) {
+                Some(index) => (&input[..index], input[index..].trim()),
+                None => (input, ""),
+            };
+            match command {
+                "s" | "step" => {
+                    debugger.mode = StepMode::Step;
+                    break;
+                },
+                "n" | "next" => {
+                    debugger.mode = StepMode::Next(self.call_stack.len());
+                    break;
+                },
+                "f" | "finish" => {
+                    debugger.mode = StepMode::Finish(self.call_stack.len());
+                    break;
+                },
+                "c" | "continue" => {
+                    debugger.mode = StepMode::Continue;
+                    break;
+                },
+                "b" | "break" => match Breakpoint::parse(argument) {
+                    Ok(breakpoint) => {
+                        if !debugger.breakpoints.contains(&breakpoint) {
+                            debugger.breakpoints.push(breakpoint);
+                        }
+                    },
+                    Err(e) => eprintln!("{}", e),
+                },
+                "d" | "delete" => match Breakpoint::parse(argument) {
+                    Ok(breakpoint) => debugger.breakpoints.retain(|b| *b != breakpoint),
+                    Err(e) => eprintln!("{}", e),
+                },
+                "B" | "breakpoints" => {
+                    for breakpoint in &debugger.breakpoints {
+                        match &breakpoint.file {
+                            Some(file) => eprintln!("{}:{}", file, breakpoint.line),
+                            None => eprintln!("{}", breakpoint.line),
+                        }
+                    }
+                },
+                "bt" | "backtrace" => self.debug_backtrace(&debugger.frames),
+                "l" | "list" => self.debug_list(line),
+                "v" | "vars" => if let Some(frame) = debugger.frames.last() {
+                    self.debug_vars(&frame.scope)
+                },
+                "p" | "print" => self.debug_print(argument),
+                "q" | "quit" => {
+                    self.exit_status = Some(0);
+                    break;
+                },
+                "h" | "help" => eprintln!("{}", HELP),
+                "" => {},
+                _ => eprintln!("Unknown command: {}\nType help for help", command),
+            }
+        }
+        true
+    }
+
+    fn current_source_file(&self) -> Option<&SourceFile> {
+        self.source_files.get(self.current_source?)
+    }
+    /// Returns name of source file of `frame`
+    pub fn frame_file(&self, frame: &Frame) -> &str {
+        match frame.source.and_then(|index| self.source_files.get(index)) {
+            Some(file) => &file.name,
+            None => "",
+        }
+    }
+    fn source_line(&self, line: usize) -> String {
+        match self.current_source_file() {
+            Some(file) => file.source.lines().nth(line - 1).unwrap_or("").to_owned(),
+            None => String::new(),
+        }
+    }
+
+    fn debug_list(&self, line: usize) {
+        let file = match self.current_source_file() {
+            Some(file) => file,
+            None => return,
+        };
+        let first = if line <= 5 { 1 } else { line - 5 };
+        for (i, text) in file.source.lines().enumerate().skip(first - 1).take(11) {
+            let marker = if i + 1 == line { "->" } else { "  " };
+            eprintln!("{} {:4} {}", marker, i + 1, text);
+        }
+    }
+
+    fn debug_backtrace(&self, frames: &[Frame]) {
+        for (i, frame) in frames.iter().enumerate().rev() {
+            let name = if frame.name.is_empty() { "<program>" } else { &frame.name };
+            eprintln!("#{} {} at {}:{}", i, name, self.frame_file(frame), frame.line);
+        }
+    }
+
+    fn debug_vars(&mut self, scope_chain: &[ScopeInfo]) {
+        let mut bindings = Vec::new();
+        for scope in scope_chain.iter().rev() {
+            if scope.scope_number == 0 {
+                continue;
+            }
+            let mut names: Vec<_> = match self.context.identifier_index.get(scope.scope_number) {
+                Some(index) => index.iter().map(|(name, id)| (name.clone(), *id)).collect(),
+                None => continue,
+            };
+            names.sort();
+            bindings.push((format!("#{} {:?}", scope.scope_number, scope.scope_type), names));
+        }
+        for (scope, names) in bindings {
+            eprintln!("{}", scope);
+            for (name, id) in names {
+                let value = self.context.get_value_from_identifier_id(id).clone();
+                eprintln!("    {} = {}", name, self.format_value(&value));
+            }
+        }
+    }
+
+    fn debug_print(&mut self, argument: &str) {
+        let expr = match parser::expression_all_consuming(argument) {
+            Ok(expr) => self.parser_expr_to_run_expr(&expr.1),
+            Err(_) => {
+                eprintln!("Parse error");
+                return
+            },
+        };
+        match self.eval(&expr, true) {
+            Ok(result) => {
+                let values: Vec<String> = result.values.iter().map(|v| self.format_value(v)).collect();
+                eprintln!("{}", values.join(" "));
+            },
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
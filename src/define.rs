@@ -1,4 +1,23 @@
 pub static VERSION: &str = "0.3.0-beta";
+
+// Exit status
+pub static EXIT_RUNTIME_ERROR: i32 = 1;
+pub static EXIT_FILE_READ_ERROR: i32 = 2;
+pub static EXIT_PREPROCESS_ERROR: i32 = 3;
+pub static EXIT_PARSE_ERROR: i32 = 4;
+pub static EXIT_CHECK_FAILED: i32 = 5;
+pub static EXIT_TEST_FAILED: i32 = 6;
+
+// Profiler
+pub static PROFILE_OUTPUT: &str = "silang.folded";
+
+// Parser
+pub static MAX_NESTING_DEPTH: usize = 256;
+
+// Interpreter
+/// Default depth limit of nested evaluation, which fits in the 8 MiB main thread stack
+pub static MAX_EVAL_DEPTH: usize = 1000;
+
 // Special
 pub static EXPRESSION_OPEN: &str = "(";
 pub static EXPRESSION_CLOSE: &str = ")";
@@ -11,6 +30,7 @@ pub static PARSER_NOT_IDENTIFIER: &str = " \t\r\n(){}[]";
 // Type name
 pub static STRING: &str = "string";
 pub static INT: &str = "int";
+pub static BIGINT: &str = "bigint";
 pub static FLOAT: &str = "float";
 pub static BOOL: &str = "bool";
 pub static VECTOR: &str = "vector";
@@ -21,6 +41,7 @@ pub static VOID: &str = "void";
 // Variables
 pub static TRUE: &str = "true";
 pub static FALSE: &str = "false";
+pub static ARGS: &str = "args";
 
 
 // Functions
@@ -37,6 +58,23 @@ pub static BREAK: &str = "break";
 pub static CONTINUE: &str = "continue";
 pub static PRINT: &str = "print";
 pub static PRINTLN: &str = "println";
+pub static EPRINT: &str = "eprint";
+pub static EPRINTLN: &str = "eprintln";
+pub static INPUT: &str = "input";
+pub static READ_LINE: &str = "read_line";
+pub static READ_ALL: &str = "read_all";
+pub static EOF: &str = "eof";
+pub static READ_FILE: &str = "read_file";
+pub static READ_LINES: &str = "read_lines";
+pub static WRITE_FILE: &str = "write_file";
+pub static APPEND_FILE: &str = "append_file";
+pub static EXISTS: &str = "exists";
+pub static REMOVE_FILE: &str = "remove_file";
+pub static LIST_DIR: &str = "list_dir";
+pub static MKDIR: &str = "mkdir";
+pub static ENV: &str = "env";
+pub static SET_ENV: &str = "set_env";
+pub static EXIT: &str = "exit";
 pub static VALUE: &str = "value";
 pub static MAKE_VECTOR: &str = "make_vector";
 pub static MAKE_MAP: &str = "make_map";
@@ -48,6 +86,23 @@ pub static SUB: &str = "-";
 pub static MUL: &str = "*";
 pub static DIV: &str = "/";
 pub static REM: &str = "%";
+pub static WRAPPING_ADD: &str = "wrapping_add";
+pub static WRAPPING_SUB: &str = "wrapping_sub";
+pub static WRAPPING_MUL: &str = "wrapping_mul";
+pub static SATURATING_ADD: &str = "saturating_add";
+pub static SATURATING_SUB: &str = "saturating_sub";
+pub static SATURATING_MUL: &str = "saturating_mul";
+
+pub static BIT_AND: &str = "&";
+pub static BIT_OR: &str = "|";
+pub static BIT_XOR: &str = "^";
+pub static BIT_NOT: &str = "~";
+pub static SHIFT_LEFT: &str = "<<";
+pub static SHIFT_RIGHT: &str = ">>";
+pub static SHIFT_RIGHT_LOGICAL: &str = ">>>";
+pub static POPCOUNT: &str = "popcount";
+pub static LEADING_ZEROS: &str = "leading_zeros";
+pub static TRAILING_ZEROS: &str = "trailing_zeros";
 
 pub static EQUAL: &str = "==";
 pub static GREATER: &str = ">";
@@ -55,6 +110,10 @@ pub static LESS: &str = "<";
 pub static GREATER_EQUAL: &str = ">=";
 pub static LESS_EQUAL: &str = "<=";
 
+pub static ASSERT: &str = "assert";
+pub static ASSERT_EQ: &str = "assert_eq";
+pub static ASSERT_ERROR: &str = "assert_error";
+
 // Others
 pub static IF: &str = "if";
 pub static LOOP: &str = "loop";
@@ -68,3 +127,11 @@ pub static TYPE_NOT_MATCHED: &str = "Type not matched";
 pub static ARGUMENT_LENGTH_MISMATCH: &str = "Argument length mismatch";
 pub static UNABLE_TO_CAST: &str = "Unable to cast";
 pub static UNSUPPORTED_OPERATION: &str = "Unsupported operation";
+pub static INTEGER_OVERFLOW: &str = "Integer overflow";
+pub static DIVISION_BY_ZERO: &str = "Division by zero";
+pub static INVALID_SHIFT_AMOUNT: &str = "Shift amount must be 0-63";
+pub static INVALID_EXIT_STATUS: &str = "Exit status must fit in 32-bit int";
+pub static FILE_SYSTEM_DISABLED: &str = "File system access is disabled";
+pub static STEP_LIMIT_EXCEEDED: &str = "Step limit exceeded";
+pub static DEPTH_LIMIT_EXCEEDED: &str = "Depth limit exceeded";
+pub static NESTING_TOO_DEEP: &str = "Nesting too deep";
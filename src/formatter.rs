@@ -0,0 +1,215 @@
+use crate::parser;
+use crate::preprocessor;
+use crate::define;
+
+static INDENT: &str = "    ";
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Open(char),
+    Close(char),
+    String(String),
+    Atom(String),
+    Comment(String),
+    Newline,
+}
+
+/// Returns `source` in canonical layout
+///
+/// Comments are kept, factors are separated by a space,
+/// blocks are indented and strings are re-escaped.
+/// Fails if `source` is not a valid program.
+pub fn format(source: &str) -> Result<String, String> {
+    let original = parse(source)?;
+    let formatted = layout(&tokenize(source)?);
+    if parse(&formatted)? != original {
+        return Err("fmt: Formatting changed the program".to_owned())
+    }
+    Ok(formatted)
+}
+
+fn parse(source: &str) -> Result<parser::Program, String> {
+    let mut buffer = source.to_owned();
+    buffer.push('\n');
+    let source_code = match preprocessor::preprocess(&buffer) {
+        Ok(source_code) => source_code,
+        Err(e) => return Err(format!("Preprocess error\n{}", e)),
+    };
+    if source_code.trim().is_empty() {
+        return Ok(parser::Program { statements: Vec::new() })
+    }
+    match parser::program_all_consuming(&source_code) {
+        Ok(program) => Ok(program.1),
+        Err(nom::Err::Error(e)) => Err(format!("Parse error\n{}", nom::error::convert_error(source_code.as_str(), e))),
+        Err(_) => Err("Parse error".to_owned()),
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => tokens.push(Token::Newline),
+            ' ' | '\t' | '\r' => {},
+            '#' => {
+                let mut comment = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c == '\n' {
+                        break;
+                    }
+                    comment.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Comment(comment.trim_end().to_owned()));
+            },
+            '"' => {
+                let mut string = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => string.push('\n'),
+                            Some('r') => string.push('\r'),
+                            Some('t') => string.push('\t'),
+                            Some('u') => {
+                                let code: String = chars.by_ref().take(4).collect();
+                                match u16::from_str_radix(&code, 16) {
+                                    Ok(code) => string.push(std::char::decode_utf16(vec![code]).next()
+                                        .and_then(|c| c.ok())
+                                        .unwrap_or(std::char::REPLACEMENT_CHARACTER)),
+                                    Err(_) => return Err(format!("fmt: Invalid escape \\u{}", code)),
+                                }
+                            },
+                            Some(c) => string.push(c),
+                            None => return Err("fmt: Unterminated string".to_owned()),
+                        },
+                        Some(c) => string.push(c),
+                        None => return Err("fmt: Unterminated string".to_owned()),
+                    }
+                }
+                tokens.push(Token::String(string));
+            },
+            '(' | '{' | '[' => tokens.push(Token::Open(c)),
+            ')' | '}' | ']' => tokens.push(Token::Close(c)),
+            _ => {
+                let mut atom = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if define::PARSER_NOT_IDENTIFIER.contains(c) || c == '#' || c == '"' {
+                        break;
+                    }
+                    atom.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Atom(atom));
+            },
+        }
+    }
+    Ok(tokens)
+}
+
+/// Escapes `string` so that `parser::string_content` reads it back
+fn escape(string: &str) -> String {
+    let mut buffer = String::from("\"");
+    for c in string.chars() {
+        match c {
+            '"' => buffer.push_str("\\\""),
+            '\\' => buffer.push_str("\\\\"),
+            '\n' => buffer.push_str("\\n"),
+            '\r' => buffer.push_str("\\r"),
+            '\t' => buffer.push_str("\\t"),
+            _ => buffer.push(c),
+        }
+    }
+    buffer.push('"');
+    buffer
+}
+
+struct Layout {
+    output: String,
+    line: String,
+    depth: usize,
+    blank_line: bool,
+}
+impl Layout {
+    fn flush(&mut self) {
+        if self.line.is_empty() {
+            return
+        }
+        if self.blank_line && !self.output.is_empty() {
+            self.output.push('\n');
+        }
+        self.blank_line = false;
+        for _ in 0..self.depth {
+            self.output.push_str(INDENT);
+        }
+        self.output.push_str(&self.line);
+        self.output.push('\n');
+        self.line = String::new();
+    }
+    fn push(&mut self, text: &str) {
+        if !self.line.is_empty() && !self.line.ends_with('(') && !self.line.ends_with('[') {
+            self.line.push(' ');
+        }
+        self.line.push_str(text);
+    }
+}
+
+fn layout(tokens: &[Token]) -> String {
+    let mut layout = Layout { output: String::new(), line: String::new(), depth: 0, blank_line: false };
+    let mut brackets = Vec::new();
+    let mut skip_newline = false;
+    for token in tokens {
+        match token {
+            Token::Newline => {
+                if skip_newline {
+                    skip_newline = false;
+                } else if brackets.last() == Some(&'(') || brackets.last() == Some(&'[') {
+                } else if layout.line.is_empty() {
+                    layout.blank_line = true;
+                } else {
+                    layout.flush();
+                }
+                continue;
+            },
+            Token::Comment(comment) => {
+                layout.push(&format!("#{}", comment));
+                layout.flush();
+                skip_newline = true;
+                continue;
+            },
+            Token::Open('{') => {
+                layout.push("{");
+                layout.flush();
+                layout.depth += 1;
+                brackets.push('{');
+                skip_newline = true;
+                continue;
+            },
+            Token::Open(c) => {
+                if *c == '[' {
+                    layout.line.push(*c);
+                } else {
+                    layout.push(&c.to_string());
+                }
+                brackets.push(*c);
+            },
+            Token::Close('}') => {
+                layout.flush();
+                layout.blank_line = false;
+                layout.depth = layout.depth.saturating_sub(1);
+                brackets.pop();
+                layout.line.push('}');
+            },
+            Token::Close(c) => {
+                layout.line.push(*c);
+                brackets.pop();
+            },
+            Token::String(string) => layout.push(&escape(string)),
+            Token::Atom(atom) => layout.push(atom),
+        }
+        skip_newline = false;
+    }
+    layout.flush();
+    layout.output
+}
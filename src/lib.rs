@@ -4,3 +4,20 @@ pub mod run;
 pub mod preprocessor;
 pub mod define;
 pub mod builtin;
+pub mod ast;
+pub mod formatter;
+pub mod lint;
+pub mod typecheck;
+pub mod lsp;
+#[cfg(any(target_family = "unix", target_family = "windows"))]
+pub mod debugger;
+#[cfg(any(target_family = "unix", target_family = "windows"))]
+pub mod dap;
+#[cfg(any(target_family = "unix", target_family = "windows"))]
+pub mod trace;
+#[cfg(any(target_family = "unix", target_family = "windows"))]
+pub mod profile;
+#[cfg(any(target_family = "unix", target_family = "windows"))]
+pub mod coverage;
+#[cfg(any(target_family = "unix", target_family = "windows"))]
+pub mod testing;
@@ -0,0 +1,320 @@
+use crate::parser::{
+    Program,
+    Statement,
+    Expression,
+    Factor,
+    Span,
+};
+use crate::builtin::{
+    self,
+    BuiltinFunction,
+};
+use crate::define;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Warning {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+/// Builtins that always evaluate to exactly one value
+static SINGLE_VALUE_BUILTINS: &[&str] = &[
+    define::ADD, define::SUB, define::MUL, define::DIV, define::REM,
+    define::WRAPPING_ADD, define::WRAPPING_SUB, define::WRAPPING_MUL,
+    define::SATURATING_ADD, define::SATURATING_SUB, define::SATURATING_MUL,
+    define::BIT_AND, define::BIT_OR, define::BIT_XOR, define::BIT_NOT,
+    define::SHIFT_LEFT, define::SHIFT_RIGHT, define::SHIFT_RIGHT_LOGICAL,
+    define::POPCOUNT, define::LEADING_ZEROS, define::TRAILING_ZEROS,
+    define::EQUAL, define::EOF, define::READ_ALL,
+    define::ENV, define::READ_FILE, define::READ_LINES, define::EXISTS, define::LIST_DIR,
+];
+
+struct Declaration {
+    name: String,
+    span: Span,
+    used: bool,
+    /// Reported as unused variable if not used
+    variable: bool,
+}
+
+struct LintScope {
+    untyped: bool,
+    declarations: Vec<Declaration>,
+}
+
+struct Linter<'a> {
+    source: &'a str,
+    builtins: Vec<BuiltinFunction>,
+    scopes: Vec<LintScope>,
+    warnings: Vec<Warning>,
+}
+
+/// Returns warnings of `program` sorted by position
+///
+/// `source` must be the preprocessed source `program` was parsed from.
+pub fn lint(source: &str, program: &Program) -> Vec<Warning> {
+    let mut linter = Linter {
+        source,
+        builtins: builtin::builtin_functions(),
+        scopes: Vec::new(),
+        warnings: Vec::new(),
+    };
+    linter.push_scope(false);
+    linter.declare(define::ARGS, Span::default(), false);
+    linter.program(program);
+    linter.pop_scope();
+    linter.warnings.sort_by_key(|w| (w.line, w.column));
+    linter.warnings
+}
+
+impl<'a> Linter<'a> {
+    fn warn(&mut self, span: Span, message: String) {
+        let (line, column) = span.line_column(self.source);
+        self.warnings.push(Warning { line, column, message });
+    }
+
+    fn push_scope(&mut self, untyped: bool) {
+        self.scopes.push(LintScope { untyped, declarations: Vec::new() });
+    }
+    fn pop_scope(&mut self) {
+        if let Some(scope) = self.scopes.pop() {
+            for declaration in scope.declarations {
+                if declaration.variable && !declaration.used && !declaration.name.starts_with('_') {
+                    self.warn(declaration.span, format!("Unused variable {}", declaration.name));
+                }
+            }
+        }
+    }
+    fn declare(&mut self, name: &str, span: Span, variable: bool) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.declarations.push(Declaration { name: name.to_owned(), span, used: false, variable });
+        }
+    }
+    fn is_declared(&self, name: &str) -> bool {
+        self.scopes.iter().any(|scope| scope.declarations.iter().any(|d| d.name == name))
+    }
+    fn is_untyped(&self) -> bool {
+        self.scopes.last().is_some_and(|scope| scope.untyped)
+    }
+    fn mark_used(&mut self, name: &str) {
+        for scope in self.scopes.iter_mut().rev() {
+            if let Some(declaration) = scope.declarations.iter_mut().rev().find(|d| d.name == name) {
+                declaration.used = true;
+                return
+            }
+        }
+    }
+    /// Returns builtin function `factor` refers to unless the name is shadowed
+    fn builtin(&self, factor: &Factor) -> Option<&BuiltinFunction> {
+        let name = factor.identifier.as_ref()?;
+        if factor.expression.is_some() || self.is_declared(name) {
+            return None
+        }
+        self.builtins.iter().find(|b| b.name == name)
+    }
+
+    fn program(&mut self, program: &Program) {
+        let mut returned = false;
+        for statement in &program.statements {
+            if returned {
+                self.warn(statement.span, "Unreachable code after return".to_owned());
+                returned = false;
+            }
+            self.statement(statement);
+            if let Some(name) = &statement.expression.factors[0].identifier {
+                returned = name == define::RETURN && self.builtin(&statement.expression.factors[0]).is_some();
+            }
+        }
+    }
+    fn statement(&mut self, statement: &Statement) {
+        self.expression(&statement.expression);
+    }
+
+    fn expression(&mut self, expression: &Expression) {
+        let (head, args) = match expression.factors.split_first() {
+            Some(split) => split,
+            None => return,
+        };
+        let builtin = self.builtin(head).map(|b| (b.name, b.min_args, b.max_args, b.evaluates_args));
+        let name = match builtin {
+            Some((name, min_args, max_args, evaluates_args)) => {
+                self.check_arity(name, min_args, max_args, evaluates_args, head.span, args);
+                name
+            },
+            None => {
+                for factor in &expression.factors {
+                    self.factor(factor);
+                }
+                return
+            },
+        };
+        if name == define::DECAS || name == define::DECAS_ALIAS {
+            if let Some(lhs) = args.first() {
+                for (name, span) in identifiers(lhs) {
+                    self.declare(&name, span, true);
+                }
+            }
+            for factor in args.iter().skip(1) {
+                self.factor(factor);
+            }
+        } else if name == define::ASSIGN || name == define::ASSIGN_DEFER {
+            if let Some(lhs) = args.first() {
+                self.assign(lhs);
+            }
+            for factor in args.iter().skip(1) {
+                self.factor(factor);
+            }
+        } else if name == define::FUNCTION_DEFINITION {
+            self.define_function(head.span, args);
+        } else if name == define::LAMBDA {
+            if let (Some(params), Some(body)) = (args.first(), args.get(1)) {
+                self.function_body(params, body);
+            }
+        } else if name == define::UNTYPED {
+            self.push_scope(true);
+            for factor in args {
+                self.body(factor);
+            }
+            self.pop_scope();
+        } else {
+            for factor in args {
+                self.factor(factor);
+            }
+        }
+    }
+
+    fn factor(&mut self, factor: &Factor) {
+        if let Some(identifier) = &factor.identifier {
+            self.mark_used(identifier);
+            if let Some(index) = &factor.expression {
+                self.expression(index);
+            }
+        } else if let Some(expression) = &factor.expression {
+            self.expression(expression);
+        } else if let Some(block) = &factor.block {
+            self.push_scope(self.is_untyped());
+            self.program(&block.program);
+            self.pop_scope();
+        }
+    }
+    /// Lints `body` in the current scope like `untyped` and function calls run their block
+    fn body(&mut self, body: &Factor) {
+        match &body.block {
+            Some(block) => self.program(&block.program),
+            None => self.factor(body),
+        }
+    }
+
+    fn assign(&mut self, lhs: &Factor) {
+        if let Some(identifier) = &lhs.identifier {
+            if let Some(index) = &lhs.expression {
+                self.mark_used(identifier);
+                self.expression(index);
+            } else if !self.is_declared(identifier) && self.builtin(lhs).is_none() {
+                if !self.is_untyped() {
+                    self.warn(lhs.span, format!("Assignment to undeclared identifier {} in typed scope", identifier));
+                }
+                self.declare(identifier, lhs.span, true);
+            }
+        } else if let Some(expression) = &lhs.expression {
+            for factor in &expression.factors {
+                self.assign(factor);
+            }
+        } else {
+            self.factor(lhs);
+        }
+    }
+
+    fn define_function(&mut self, span: Span, args: &[Factor]) {
+        if let Some(name) = args.first() {
+            if let Some(identifier) = &name.identifier {
+                self.declare(identifier, name.span, false);
+            }
+        }
+        if !self.is_untyped() && args.len() == 3 {
+            self.warn(span, diagnostic(define::FUNCTION_DEFINITION, "Argument length must be 4"));
+        }
+        match args.last() {
+            Some(body) if body.block.is_some() && args.len() >= 3 => {
+                for factor in &args[2..args.len() - 1] {
+                    self.factor(factor);
+                }
+                self.function_body(&args[1], body);
+            },
+            _ => self.warn(span, diagnostic(define::FUNCTION_DEFINITION, "Function body must be block")),
+        }
+    }
+    fn function_body(&mut self, params: &Factor, body: &Factor) {
+        self.push_scope(false);
+        for (name, span) in identifiers(params) {
+            self.declare(&name, span, false);
+        }
+        self.body(body);
+        self.pop_scope();
+    }
+
+    fn check_arity(&mut self, name: &str, min: usize, max: Option<usize>, evaluates_args: bool, span: Span, args: &[Factor]) {
+        let length = if evaluates_args {
+            let mut length = 0;
+            for factor in args {
+                match self.value_length(factor) {
+                    Some(l) => length += l,
+                    None => return,
+                }
+            }
+            length
+        } else {
+            args.len()
+        };
+        if length < min || max.is_some_and(|max| max < length) {
+            let expected = match max {
+                Some(max) if max == min => format!("{}", min),
+                Some(max) => format!("{}-{}", min, max),
+                None => format!(">={}", min),
+            };
+            self.warn(span, diagnostic(name, &format!("Argument length must be {}", expected)));
+        }
+    }
+    /// Returns the number of values `factor` evaluates to if it is known statically
+    fn value_length(&self, factor: &Factor) -> Option<usize> {
+        if factor.string.is_some() || factor.int.is_some() || factor.float.is_some() {
+            Some(1)
+        } else if factor.identifier.is_some() {
+            if self.builtin(factor).is_some() {
+                None
+            } else {
+                Some(1)
+            }
+        } else if let Some(expression) = &factor.expression {
+            match expression.factors.first().and_then(|head| self.builtin(head)) {
+                Some(builtin) if SINGLE_VALUE_BUILTINS.contains(&builtin.name) => Some(1),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+}
+
+/// Prefixes `message` with function `name` like runtime errors
+fn diagnostic(name: &str, message: &str) -> String {
+    if name.ends_with(':') {
+        format!("{} {}", name, message)
+    } else {
+        format!("{}: {}", name, message)
+    }
+}
+
+/// Returns identifiers in `x` or `(x y ...)` with their positions
+fn identifiers(factor: &Factor) -> Vec<(String, Span)> {
+    let mut names = Vec::new();
+    if let Some(identifier) = &factor.identifier {
+        names.push((identifier.clone(), factor.span));
+    } else if let Some(expression) = &factor.expression {
+        for f in &expression.factors {
+            names.append(&mut identifiers(f));
+        }
+    }
+    names
+}
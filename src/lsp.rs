@@ -0,0 +1,597 @@
+use crate::parser::{
+    self,
+    Program,
+    Expression,
+    Factor,
+    Span,
+};
+use crate::preprocessor;
+use crate::builtin;
+use crate::typecheck;
+use crate::silang::SILType;
+use crate::define;
+
+use std::collections::HashMap;
+use std::io::{
+    self,
+    BufRead,
+    Write,
+};
+use std::path::Path;
+
+use serde_json::{
+    json,
+    Value as Json,
+};
+
+// LSP constants
+static SEVERITY_ERROR: i64 = 1;
+static SYMBOL_KIND_FUNCTION: i64 = 12;
+static COMPLETION_KIND_FUNCTION: i64 = 3;
+static COMPLETION_KIND_VARIABLE: i64 = 6;
+static COMPLETION_KIND_KEYWORD: i64 = 14;
+static METHOD_NOT_FOUND: i64 = -32601;
+
+/// Name bound in a document
+#[derive(Debug, Clone)]
+pub struct Definition {
+    pub name: String,
+    /// 0-based line and UTF-16 column of the name
+    pub line: usize,
+    pub character: usize,
+    pub function: bool,
+    /// Declared type or signature shown on hover
+    pub detail: String,
+}
+
+/// Parsed document
+pub struct Document {
+    pub text: String,
+    source: Option<String>,
+    program: Option<Program>,
+}
+impl Document {
+    pub fn new(text: &str) -> Self {
+        let mut buffer = text.to_owned();
+        buffer.push('\n');
+        let source = preprocessor::preprocess(&buffer).ok();
+        let program = source.as_ref().and_then(|source| {
+            parser::program_all_consuming(source).ok().map(|program| program.1)
+        });
+        Document { text: text.to_owned(), source, program }
+    }
+
+    /// Returns LSP diagnostics of preprocess and parse errors
+    pub fn diagnostics(&self) -> Vec<Json> {
+        let mut buffer = self.text.clone();
+        buffer.push('\n');
+        if let Err(error) = preprocessor::source_code_all_consuming(&buffer) {
+            return vec![diagnostic(&buffer, error, "Preprocess error")]
+        }
+        if let Some(source) = &self.source {
+            if source.trim().is_empty() {
+                return Vec::new()
+            }
+            if let Err(error) = parser::program_all_consuming(source) {
+                return vec![diagnostic(source, error, "Parse error")]
+            }
+        }
+        Vec::new()
+    }
+
+    /// Returns names bound by `decas`, `=` and `f:` in order of appearance
+    pub fn definitions(&self) -> Vec<Definition> {
+        match (&self.source, &self.program) {
+            (Some(source), Some(program)) => {
+                let mut collector = Collector::new(source, None);
+                collector.program(program);
+                collector.definitions
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the first definition of `name` in the top-level scope
+    pub fn global_definition(&self, name: &str) -> Option<Definition> {
+        let (source, program) = (self.source.as_ref()?, self.program.as_ref()?);
+        let mut collector = Collector::new(source, None);
+        collector.program(program);
+        collector.definitions.into_iter().zip(collector.scopes)
+            .find(|(d, scope)| d.name == name && *scope == 0)
+            .map(|(d, _)| d)
+    }
+
+    /// Returns definition of `name` visible from the identifier at 0-based `line` and UTF-16 `character`
+    pub fn resolve(&self, name: &str, line: usize, character: usize) -> Option<Definition> {
+        let (source, program) = (self.source.as_ref()?, self.program.as_ref()?);
+        let mut collector = Collector::new(source, Some((line, character)));
+        collector.program(program);
+        collector.resolve(name)
+    }
+
+    /// Returns module names of `import` statements
+    pub fn imports(&self) -> Vec<String> {
+        let mut imports = Vec::new();
+        if let Some(program) = &self.program {
+            for statement in &program.statements {
+                let factors = &statement.expression.factors;
+                if factors[0].identifier.as_deref() == Some(define::IMPORT) {
+                    for factor in &factors[1..] {
+                        if let Some(string) = &factor.string {
+                            imports.push(string.clone());
+                        }
+                    }
+                }
+            }
+        }
+        imports
+    }
+
+    /// Returns identifier or string at 0-based `line` and UTF-16 `character`
+    pub fn word_at(&self, line: usize, character: usize) -> Option<String> {
+        let text = self.text.lines().nth(line)?;
+        let chars: Vec<char> = text.chars().collect();
+        let mut index = 0;
+        let mut units = 0;
+        while index < chars.len() && units + chars[index].len_utf16() <= character {
+            units += chars[index].len_utf16();
+            index += 1;
+        }
+        let is_word = |c: &char| !define::PARSER_NOT_IDENTIFIER.contains(*c) && *c != '#' && *c != '"';
+        let mut start = index;
+        while 0 < start && is_word(&chars[start - 1]) {
+            start -= 1;
+        }
+        let mut end = index;
+        while end < chars.len() && is_word(&chars[end]) {
+            end += 1;
+        }
+        if start == end {
+            return None
+        }
+        Some(chars[start..end].iter().collect())
+    }
+}
+
+fn diagnostic(input: &str, error: nom::Err<nom::error::VerboseError<&str>>, title: &str) -> Json {
+    let (rest, message) = match error {
+        nom::Err::Error(e) | nom::Err::Failure(e) => {
+            let rest = e.errors.first().map_or(0, |(rest, _)| rest.len());
+            (rest, format!("{}\n{}", title, nom::error::convert_error(input, e)))
+        },
+        nom::Err::Incomplete(_) => (0, title.to_owned()),
+    };
+    let (line, character) = position(input, Span { rest, position: None });
+    json!({
+        "range": range(line, character, 1),
+        "severity": SEVERITY_ERROR,
+        "source": "silang",
+        "message": message,
+    })
+}
+
+/// Returns 0-based line and UTF-16 column of `span` in `source`
+fn position(source: &str, span: Span) -> (usize, usize) {
+    let offset = source.len().saturating_sub(span.rest);
+    let before = source.get(..offset).unwrap_or(source);
+    let line = before.matches('\n').count();
+    let character = before.rsplit('\n').next().unwrap_or("").encode_utf16().count();
+    (line, character)
+}
+fn range(line: usize, character: usize, length: usize) -> Json {
+    json!({
+        "start": { "line": line, "character": character },
+        "end": { "line": line, "character": character + length },
+    })
+}
+
+/// Walks a program collecting definitions with the scope they are bound in
+struct Collector<'a> {
+    source: &'a str,
+    definitions: Vec<Definition>,
+    /// Scope of each definition
+    scopes: Vec<usize>,
+    /// Enclosing scopes, innermost last
+    stack: Vec<usize>,
+    next_scope: usize,
+    /// Position to resolve and enclosing scopes of identifier at it
+    target: Option<(usize, usize)>,
+    target_stack: Option<Vec<usize>>,
+}
+impl<'a> Collector<'a> {
+    fn new(source: &'a str, target: Option<(usize, usize)>) -> Self {
+        Collector {
+            source,
+            definitions: Vec::new(),
+            scopes: Vec::new(),
+            stack: vec![0],
+            next_scope: 1,
+            target,
+            target_stack: None,
+        }
+    }
+
+    fn push_scope(&mut self) {
+        self.stack.push(self.next_scope);
+        self.next_scope += 1;
+    }
+    fn pop_scope(&mut self) {
+        self.stack.pop();
+    }
+    fn define(&mut self, name: &str, span: Span, function: bool, detail: String) {
+        self.definitions.push(definition(self.source, name, span, function, detail));
+        self.scopes.push(*self.stack.last().unwrap_or(&0));
+    }
+    fn is_visible(&self, name: &str) -> bool {
+        self.definitions.iter().zip(&self.scopes).any(|(d, scope)| d.name == name && self.stack.contains(scope))
+    }
+    /// Remembers enclosing scopes if `factor` is identifier at target position
+    fn mark(&mut self, factor: &Factor) {
+        if let (Some(identifier), Some((line, character))) = (&factor.identifier, self.target) {
+            let (start_line, start) = position(self.source, factor.span);
+            if start_line == line && start <= character && character <= start + identifier.encode_utf16().count() {
+                self.target_stack = Some(self.stack.clone());
+            }
+        }
+    }
+
+    fn program(&mut self, program: &Program) {
+        for statement in &program.statements {
+            self.expression(&statement.expression);
+        }
+    }
+    fn expression(&mut self, expression: &Expression) {
+        let factors = &expression.factors;
+        let head = factors[0].identifier.as_deref().unwrap_or("");
+        if (head == define::DECAS || head == define::DECAS_ALIAS) && factors.len() == 3 {
+            let names = identifiers(&factors[1]);
+            let types = identifiers(&factors[2]);
+            for (i, (name, span)) in names.into_iter().enumerate() {
+                let type_name = types.get(i).map_or(define::VOID.to_owned(), |t| t.0.clone());
+                self.define(&name, span, false, format!("{}: {}", name, type_name));
+            }
+        } else if (head == define::ASSIGN || head == define::ASSIGN_DEFER) && factors.len() == 3 {
+            for (name, span) in identifiers(&factors[1]) {
+                if !self.is_visible(&name) {
+                    self.define(&name, span, false, format!("{}: {}", name, typecheck::type_name(&SILType::Any)));
+                }
+            }
+        } else if head == define::FUNCTION_DEFINITION && 4 <= factors.len() && factors[factors.len() - 1].block.is_some() {
+            if let Some(name) = &factors[1].identifier {
+                let params: Vec<String> = identifiers(&factors[2]).into_iter().map(|p| p.0).collect();
+                let mut detail = format!("{} {} ({})", define::FUNCTION_DEFINITION, name, params.join(" "));
+                if factors.len() == 5 {
+                    if let Some(return_type) = &factors[3].identifier {
+                        detail.push(' ');
+                        detail.push_str(return_type);
+                    }
+                }
+                self.define(name, factors[1].span, true, detail);
+            }
+            self.mark(&factors[0]);
+            self.mark(&factors[1]);
+            self.function(&factors[2], &factors[factors.len() - 1]);
+            return
+        } else if head == define::LAMBDA && factors.len() == 3 {
+            self.mark(&factors[0]);
+            self.function(&factors[1], &factors[2]);
+            return
+        }
+        for factor in factors {
+            self.factor(factor);
+        }
+    }
+    /// Binds `params` in a new scope around `body`
+    fn function(&mut self, params: &Factor, body: &Factor) {
+        self.push_scope();
+        for (param, span) in identifiers(params) {
+            self.define(&param, span, false, format!("{}: {}", param, typecheck::type_name(&SILType::Any)));
+        }
+        self.factor(params);
+        self.factor(body);
+        self.pop_scope();
+    }
+    fn factor(&mut self, factor: &Factor) {
+        self.mark(factor);
+        if let Some(expression) = &factor.expression {
+            self.expression(expression);
+        } else if let Some(block) = &factor.block {
+            self.push_scope();
+            self.program(&block.program);
+            self.pop_scope();
+        }
+    }
+
+    /// Returns definition of `name` visible from target, preferring the last one before it
+    fn resolve(&self, name: &str) -> Option<Definition> {
+        let (line, character) = self.target?;
+        let stack = self.target_stack.as_ref()?;
+        let candidates = || self.definitions.iter().zip(&self.scopes).filter(|(d, _)| d.name == name);
+        for scope in stack.iter().rev() {
+            let before = candidates().rfind(|(d, s)| *s == scope && (d.line, d.character) <= (line, character));
+            if let Some((definition, _)) = before {
+                return Some(definition.clone())
+            }
+        }
+        for scope in stack.iter().rev() {
+            if let Some((definition, _)) = candidates().find(|(_, s)| *s == scope) {
+                return Some(definition.clone())
+            }
+        }
+        None
+    }
+}
+fn definition(source: &str, name: &str, span: Span, function: bool, detail: String) -> Definition {
+    let (line, character) = position(source, span);
+    Definition { name: name.to_owned(), line, character, function, detail }
+}
+fn identifiers(factor: &Factor) -> Vec<(String, Span)> {
+    let mut names = Vec::new();
+    if let Some(identifier) = &factor.identifier {
+        names.push((identifier.clone(), factor.span));
+    } else if let Some(expression) = &factor.expression {
+        for f in &expression.factors {
+            names.append(&mut identifiers(f));
+        }
+    }
+    names
+}
+
+fn path_to_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for c in path.to_string_lossy().chars() {
+        match c {
+            ' ' => uri.push_str("%20"),
+            '%' => uri.push_str("%25"),
+            _ => uri.push(c),
+        }
+    }
+    uri
+}
+
+/// Language server state
+pub struct Server {
+    documents: HashMap<String, Document>,
+    shutdown: bool,
+}
+impl Default for Server {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Server {
+    pub fn new() -> Self {
+        Server { documents: HashMap::new(), shutdown: false }
+    }
+
+    /// Handles `message` and returns messages to be sent,
+    /// or exit status if the client requested exit
+    pub fn handle(&mut self, message: &Json) -> Result<Vec<Json>, i32> {
+        let method = message["method"].as_str().unwrap_or("");
+        let params = &message["params"];
+        let id = message.get("id").cloned();
+        let result = match method {
+            "initialize" => json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "definitionProvider": true,
+                    "hoverProvider": true,
+                    "documentSymbolProvider": true,
+                    "completionProvider": {},
+                },
+                "serverInfo": { "name": "silang-lsp", "version": define::VERSION },
+            }),
+            "shutdown" => {
+                self.shutdown = true;
+                Json::Null
+            },
+            "exit" => return Err(if self.shutdown { 0 } else { 1 }),
+            "textDocument/didOpen" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or("");
+                let text = params["textDocument"]["text"].as_str().unwrap_or("");
+                return Ok(vec![self.update(uri, text)])
+            },
+            "textDocument/didChange" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or("");
+                return match params["contentChanges"].as_array().and_then(|changes| changes.last()) {
+                    Some(change) => Ok(vec![self.update(uri, change["text"].as_str().unwrap_or(""))]),
+                    None => Ok(Vec::new()),
+                }
+            },
+            "textDocument/didClose" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or("");
+                self.documents.remove(uri);
+                return Ok(vec![notification("textDocument/publishDiagnostics", json!({ "uri": uri, "diagnostics": [] }))])
+            },
+            "textDocument/definition" => self.definition(params),
+            "textDocument/hover" => self.hover(params),
+            "textDocument/documentSymbol" => self.document_symbol(params),
+            "textDocument/completion" => self.completion(params),
+            _ => {
+                return match id {
+                    Some(id) => Ok(vec![json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": { "code": METHOD_NOT_FOUND, "message": format!("Unknown method: {}", method) },
+                    })]),
+                    None => Ok(Vec::new()),
+                }
+            },
+        };
+        match id {
+            Some(id) => Ok(vec![json!({ "jsonrpc": "2.0", "id": id, "result": result })]),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn update(&mut self, uri: &str, text: &str) -> Json {
+        let document = Document::new(text);
+        let diagnostics = document.diagnostics();
+        self.documents.insert(uri.to_owned(), document);
+        notification("textDocument/publishDiagnostics", json!({ "uri": uri, "diagnostics": diagnostics }))
+    }
+
+    /// Returns document, name and definition of the word at position of `params`
+    fn lookup(&self, params: &Json) -> Option<(String, Option<(String, Definition)>)> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        let document = self.documents.get(uri)?;
+        let line = params["position"]["line"].as_u64()? as usize;
+        let character = params["position"]["character"].as_u64()? as usize;
+        let word = document.word_at(line, character)?;
+        if let Some(definition) = document.resolve(&word, line, character) {
+            return Some((word.clone(), Some((uri.to_owned(), definition))))
+        }
+        // Modules are read the same way as `import` does, relative to the working directory
+        let directory = std::env::current_dir().unwrap_or_default();
+        for module in document.imports() {
+            let path = directory.join(builtin::module_file_name(&module));
+            if let Ok(text) = std::fs::read_to_string(&path) {
+                if let Some(definition) = Document::new(&text).global_definition(&word) {
+                    return Some((word.clone(), Some((path_to_uri(&path), definition))))
+                }
+            }
+        }
+        Some((word, None))
+    }
+
+    fn definition(&self, params: &Json) -> Json {
+        match self.lookup(params) {
+            Some((word, Some((uri, definition)))) => json!({
+                "uri": uri,
+                "range": range(definition.line, definition.character, word.encode_utf16().count()),
+            }),
+            _ => Json::Null,
+        }
+    }
+
+    fn hover(&self, params: &Json) -> Json {
+        let (word, found) = match self.lookup(params) {
+            Some(lookup) => lookup,
+            None => return Json::Null,
+        };
+        let contents = match found {
+            Some((_, definition)) => definition.detail,
+            None => {
+                if builtin::builtin_functions().iter().any(|b| b.name == word) {
+                    format!("{}: builtin {}", word, define::FUNCTION)
+                } else if word == define::TRUE || word == define::FALSE {
+                    format!("{}: {}", word, define::BOOL)
+                } else {
+                    return Json::Null
+                }
+            },
+        };
+        json!({ "contents": { "kind": "plaintext", "value": contents } })
+    }
+
+    fn document_symbol(&self, params: &Json) -> Json {
+        let document = match params["textDocument"]["uri"].as_str().and_then(|uri| self.documents.get(uri)) {
+            Some(document) => document,
+            None => return Json::Null,
+        };
+        let symbols: Vec<Json> = document.definitions().into_iter()
+            .filter(|d| d.function)
+            .map(|d| {
+                let range = range(d.line, d.character, d.name.encode_utf16().count());
+                json!({
+                    "name": d.name,
+                    "detail": d.detail,
+                    "kind": SYMBOL_KIND_FUNCTION,
+                    "range": range,
+                    "selectionRange": range,
+                })
+            })
+            .collect();
+        json!(symbols)
+    }
+
+    fn completion(&self, params: &Json) -> Json {
+        let mut items = Vec::new();
+        for builtin in builtin::builtin_functions() {
+            items.push(json!({ "label": builtin.name, "kind": COMPLETION_KIND_FUNCTION, "detail": "builtin" }));
+        }
+        for name in &[define::STRING, define::INT, define::BIGINT, define::FLOAT, define::BOOL, define::VECTOR, define::VOID, define::TRUE, define::FALSE] {
+            items.push(json!({ "label": name, "kind": COMPLETION_KIND_KEYWORD }));
+        }
+        if let Some(document) = params["textDocument"]["uri"].as_str().and_then(|uri| self.documents.get(uri)) {
+            let mut names = Vec::new();
+            for definition in document.definitions() {
+                if names.contains(&definition.name) {
+                    continue;
+                }
+                names.push(definition.name.clone());
+                let kind = if definition.function { COMPLETION_KIND_FUNCTION } else { COMPLETION_KIND_VARIABLE };
+                items.push(json!({ "label": definition.name, "kind": kind, "detail": definition.detail }));
+            }
+        }
+        json!(items)
+    }
+}
+
+fn notification(method: &str, params: Json) -> Json {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+/// Reads a JSON-RPC message framed with `Content-Length` header
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Json>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None)
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let length = match length {
+        Some(length) => length,
+        None => return Err(io::Error::new(io::ErrorKind::InvalidData, "Missing Content-Length")),
+    };
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    match serde_json::from_slice(&body) {
+        Ok(json) => Ok(Some(json)),
+        Err(e) => Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+    }
+}
+/// Writes `message` framed with `Content-Length` header
+pub fn write_message(writer: &mut impl Write, message: &Json) -> io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+/// Serves LSP over stdio until exit and returns exit status
+pub fn run() -> i32 {
+    let stdin = io::stdin();
+    let mut reader = stdin.lock();
+    let stdout = io::stdout();
+    let mut writer = stdout.lock();
+    let mut server = Server::new();
+    loop {
+        let message = match read_message(&mut reader) {
+            Ok(Some(message)) => message,
+            Ok(None) => return 1,
+            Err(e) => {
+                eprintln!("{}", e);
+                return 1
+            },
+        };
+        match server.handle(&message) {
+            Ok(responses) => {
+                for response in responses {
+                    if let Err(e) = write_message(&mut writer, &response) {
+                        eprintln!("{}", e);
+                        return 1
+                    }
+                }
+            },
+            Err(status) => return status,
+        }
+    }
+}
@@ -4,18 +4,30 @@ mod run;
 mod preprocessor;
 mod define;
 mod builtin;
+mod repl;
+mod ast;
+mod formatter;
+mod lint;
+mod typecheck;
+mod debugger;
+mod trace;
+mod profile;
+mod coverage;
+mod testing;
 
 use std::fs;
 use std::io::{
     self,
     Read,
-    Write,
 };
 
 extern crate clap;
 use clap::{
     Arg,
     App,
+    AppSettings,
+    ArgMatches,
+    SubCommand,
 };
 
 fn main() {
@@ -25,105 +37,424 @@ fn main() {
         .version(interpreter.version)
         .author("Kaoru Chisen <cordx56@cordx.net>")
         .about("Run SILang code")
+        .setting(AppSettings::TrailingVarArg)
+        .setting(AppSettings::AllowExternalSubcommands)
         .arg(Arg::with_name("FILE")
              .help("Input file to run"))
+        .arg(Arg::with_name("ARGS")
+             .help("Arguments passed to the script as args")
+             .multiple(true))
+        .arg(Arg::with_name("check")
+             .long("check")
+             .help("Only preprocess and parse FILE and report errors")
+             .takes_value(false))
         .arg(Arg::with_name("parseTree")
              .long("parseTree")
              .help("Print parse tree")
              .takes_value(false))
+        .arg(Arg::with_name("astFormat")
+             .long("ast-format")
+             .help("Print AST in FORMAT")
+             .value_name("FORMAT")
+             .possible_values(&[ast::JSON, ast::SEXP])
+             .takes_value(true))
+        .arg(Arg::with_name("readAst")
+             .long("read-ast")
+             .help("Read FILE as AST in FORMAT instead of source code")
+             .value_name("FORMAT")
+             .possible_values(&[ast::JSON, ast::SEXP])
+             .takes_value(true))
+        .arg(Arg::with_name("debug")
+             .long("debug")
+             .help("Run FILE in interactive debugger")
+             .takes_value(false))
+        .arg(Arg::with_name("break")
+             .long("break")
+             .help("Set breakpoint at [FILE:]LINE for --debug")
+             .value_name("LINE")
+             .multiple(true)
+             .number_of_values(1)
+             .takes_value(true))
+        .arg(Arg::with_name("trace")
+             .long("trace")
+             .help("Log function calls with arguments and results to stderr")
+             .takes_value(false))
+        .arg(Arg::with_name("traceOutput")
+             .long("trace-output")
+             .help("Write --trace log to FILE instead of stderr")
+             .value_name("FILE")
+             .requires("trace")
+             .takes_value(true))
+        .arg(Arg::with_name("traceFilter")
+             .long("trace-filter")
+             .help("Log only calls of function NAME for --trace")
+             .value_name("NAME")
+             .requires("trace")
+             .multiple(true)
+             .number_of_values(1)
+             .takes_value(true))
+        .arg(Arg::with_name("profile")
+             .long("profile")
+             .help("Print call and statement timings to stderr and write folded stacks")
+             .takes_value(false))
+        .arg(Arg::with_name("profileOutput")
+             .long("profile-output")
+             .help("Write --profile folded stacks to FILE")
+             .value_name("FILE")
+             .requires("profile")
+             .takes_value(true))
+        .arg(Arg::with_name("coverage")
+             .long("coverage")
+             .help("Write statement and branch coverage of FILE and imported modules to LCOV_FILE")
+             .value_name("LCOV_FILE")
+             .takes_value(true))
+        .subcommand(SubCommand::with_name("fmt")
+                    .about("Format SILang code")
+                    .arg(Arg::with_name("FILE")
+                         .help("Files to format in place, or - for stdin")
+                         .multiple(true))
+                    .arg(Arg::with_name("check")
+                         .long("check")
+                         .help("Only report files which are not formatted")
+                         .takes_value(false)))
+        .subcommand(SubCommand::with_name("lint")
+                    .about("Report common mistakes in SILang code")
+                    .arg(Arg::with_name("FILE")
+                         .help("Files to lint, or - for stdin")
+                         .multiple(true)))
+        .subcommand(SubCommand::with_name("check")
+                    .about("Check SILang code without running it")
+                    .arg(Arg::with_name("FILE")
+                         .help("Files to check, or - for stdin")
+                         .multiple(true))
+                    .arg(Arg::with_name("types")
+                         .long("types")
+                         .help("Check types of typed scopes")
+                         .takes_value(false)))
+        .subcommand(SubCommand::with_name("test")
+                    .about("Run test_* functions in *_test.sil files")
+                    .arg(Arg::with_name("PATH")
+                         .help("Test files, or directories to search for *_test.sil files")
+                         .multiple(true)))
         .get_matches();
 
-    let mut buffer = String::new();
+    if let Some(matches) = matches.subcommand_matches("fmt") {
+        fmt(matches);
+        return
+    }
+    if let Some(matches) = matches.subcommand_matches("lint") {
+        lint(matches);
+        return
+    }
+    if let Some(matches) = matches.subcommand_matches("check") {
+        check(matches);
+        return
+    }
+    if let Some(matches) = matches.subcommand_matches("test") {
+        test(matches);
+        return
+    }
+
+    let script_args: Vec<String> = match matches.values_of("ARGS") {
+        Some(values) => values.map(|v| v.to_owned()).collect(),
+        None => Vec::new(),
+    };
+    interpreter.set_args(&script_args);
+
     match matches.value_of("FILE") {
         Some(i) => {
-            if i == "-" {
-                let stdin = io::stdin();
-                let mut handle = stdin.lock();
-                handle.read_to_string(&mut buffer).ok();
-            } else {
-                match fs::read_to_string(i) {
-                    Ok(s) => {
-                        buffer = s;
-                    },
-                    Err(e) => {
-                        eprintln!("File read error");
-                        eprintln!("{}", e);
-                        return
-                    },
-                }
-            }
+            let mut buffer = read_source(i);
             buffer.push_str("\n");
 
-            match preprocessor::preprocess(&buffer) {
-                Ok(source_code) => {
-                    let parse_result = parser::program_all_consuming(&source_code);
-                    // eprintln!("{:?}", parse_result);
-                    match parse_result {
-                        Ok(program) => {
-                            if matches.is_present("parseTree") {
-                                println!("{}", parser::parse_tree_program(&program.1, 0));
-                            } else {
-                                match interpreter.run(&program.1) {
-                                    Ok(_) => {},
-                                    Err(e) => eprintln!("{}", e),
-                                }
-                            }
-                        },
-                        Err(error) => {
-                            eprintln!("Parse error");
-                            match error {
-                                nom::Err::Error(e) => {
-                                    let input: &str = &buffer;
-                                    eprintln!("{}", nom::error::convert_error(input, e))
-                                },
-                                _ => {},
-                            }
+            let (program, source_code) = match matches.value_of("readAst") {
+                Some(format) => {
+                    match ast::read_program(&buffer, format) {
+                        Ok(program) => (program, String::new()),
+                        Err(e) => {
+                            eprintln!("AST read error\n{}", e);
+                            std::process::exit(define::EXIT_PARSE_ERROR);
                         },
                     }
                 },
-                Err(e) => {
-                    eprintln!("Preprocess error\n{}", e);
-                }
+                None => {
+                    let source_code = preprocess(&buffer);
+                    let program = parse(&source_code);
+                    (program, source_code)
+                },
+            };
+            interpreter.current_source = Some(interpreter.add_source_file(i, &source_code));
+            if matches.is_present("check") {
+                return
             }
-        },
-        None => {
-            println!("SILang Interpreter Ver:{}", interpreter.version);
-            loop {
-                if 0 < buffer.len() {
-                    print!(". ");
-                } else {
-                    print!("> ");
-                }
-                std::io::stdout().flush().ok();
-                std::io::stdin().read_line(&mut buffer).ok();
-                if buffer.len() == 0 {
-                    break;
+            if let Some(format) = matches.value_of("astFormat") {
+                match ast::write_program(&program, &source_code, format) {
+                    Ok(s) => println!("{}", s),
+                    Err(e) => eprintln!("{}", e),
                 }
-                match parser::statement_all_consuming(&buffer) {
-                    Ok (s) => {
-                        if matches.is_present("parseTree") {
-                            println!("{}", parser::parse_tree_statement(&s.1, 0));
-                        } else {
-                            match interpreter.exec(&s.1) {
-                                Ok(result) => {
-                                    for v in result.values {
-                                        interpreter.print_value(&v).ok();
-                                        print!(" ");
-                                    }
-                                    println!("");
-                                },
-                                Err(e) => {
-                                    eprintln!("{}", e);
-                                },
-                            }
+            } else if matches.is_present("parseTree") {
+                println!("{}", parser::parse_tree_program(&program, 0));
+            } else {
+                if matches.is_present("debug") {
+                    let mut breakpoints = Vec::new();
+                    for value in matches.values_of("break").into_iter().flatten() {
+                        match debugger::Breakpoint::parse(value) {
+                            Ok(breakpoint) => breakpoints.push(breakpoint),
+                            Err(e) => {
+                                eprintln!("{}", e);
+                                std::process::exit(define::EXIT_RUNTIME_ERROR);
+                            },
                         }
-                        buffer = String::new();
-                    },
-                    Err(_) => {
-                        continue;
-                    },
+                    }
+                    interpreter.debugger = Some(debugger::Debugger::new(breakpoints));
+                }
+                if matches.is_present("trace") {
+                    interpreter.tracer = Some(tracer(&matches));
+                }
+                if matches.is_present("profile") {
+                    interpreter.profiler = Some(profile::Profiler::new());
+                }
+                if matches.is_present("coverage") {
+                    interpreter.coverage = Some(coverage::Coverage::new());
+                }
+                let result = interpreter.run(&program);
+                if let Some(profiler) = interpreter.profiler.take() {
+                    eprint!("{}", interpreter.profile_summary(&profiler));
+                    let file = matches.value_of("profileOutput").unwrap_or(define::PROFILE_OUTPUT);
+                    if let Err(e) = fs::write(file, profiler.folded_stacks()) {
+                        eprintln!("{}: {}", file, e);
+                    }
+                }
+                if let Some(coverage) = interpreter.coverage.take() {
+                    let file = matches.value_of("coverage").unwrap_or_default();
+                    if let Err(e) = fs::write(file, interpreter.coverage_lcov(&coverage)) {
+                        eprintln!("{}: {}", file, e);
+                    }
+                }
+                if let Some(status) = interpreter.exit_status {
+                    std::process::exit(status);
+                }
+                if let Err(e) = result {
+                    eprintln!("{}", e);
+                    std::process::exit(define::EXIT_RUNTIME_ERROR);
                 }
             }
         },
+        None => {
+            repl::run(&mut interpreter, &script_args, matches.is_present("parseTree"));
+        },
+    }
+}
+
+/// Returns tracer writing to `--trace-output` or stderr, or exits with error status
+fn tracer(matches: &ArgMatches) -> trace::Tracer {
+    let output: Box<dyn io::Write> = match matches.value_of("traceOutput") {
+        Some(file) => match fs::File::create(file) {
+            Ok(file) => Box::new(io::BufWriter::new(file)),
+            Err(e) => {
+                eprintln!("{}: {}", file, e);
+                std::process::exit(define::EXIT_FILE_READ_ERROR);
+            },
+        },
+        None => Box::new(io::stderr()),
+    };
+    let filters = matches.values_of("traceFilter").into_iter().flatten().map(|name| name.to_owned()).collect();
+    trace::Tracer::new(output, filters)
+}
+
+/// Reads `file`, or stdin if `file` is `-`, or exits with error status
+fn read_source(file: &str) -> String {
+    let mut buffer = String::new();
+    let read = if file == "-" {
+        io::stdin().read_to_string(&mut buffer).map(|_| ())
+    } else {
+        fs::read_to_string(file).map(|s| buffer = s)
+    };
+    if let Err(e) = read {
+        eprintln!("File read error");
+        eprintln!("{}", e);
+        std::process::exit(define::EXIT_FILE_READ_ERROR);
+    }
+    buffer
+}
+
+/// Preprocesses `buffer`, or exits with error status
+fn preprocess(buffer: &str) -> String {
+    match preprocessor::preprocess(buffer) {
+        Ok(source_code) => source_code,
+        Err(e) => {
+            eprintln!("Preprocess error\n{}", e);
+            std::process::exit(define::EXIT_PREPROCESS_ERROR);
+        },
+    }
+}
+
+/// Parses preprocessed `source_code`, or exits with error status
+fn parse(source_code: &str) -> parser::Program {
+    let parse_result = parser::program_all_consuming(source_code);
+    // eprintln!("{:?}", parse_result);
+    match parse_result {
+        Ok(program) => program.1,
+        Err(error) => {
+            eprintln!("Parse error");
+            if let nom::Err::Error(e) = error {
+                eprintln!("{}", nom::error::convert_error(source_code, e))
+            }
+            std::process::exit(define::EXIT_PARSE_ERROR);
+        },
+    }
+}
+
+/// Formats files in place, or reports unformatted files with `--check`
+fn fmt(matches: &ArgMatches) {
+    let files: Vec<&str> = match matches.values_of("FILE") {
+        Some(values) => values.collect(),
+        None => vec!["-"],
+    };
+    let mut unformatted = false;
+    for file in files {
+        let buffer = read_source(file);
+        let formatted = match formatter::format(&buffer) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                eprintln!("{}: {}", file, e);
+                std::process::exit(define::EXIT_PARSE_ERROR);
+            },
+        };
+        if matches.is_present("check") {
+            if formatted != buffer {
+                println!("{}", file);
+                unformatted = true;
+            }
+        } else if file == "-" {
+            print!("{}", formatted);
+        } else if formatted != buffer {
+            if let Err(e) = fs::write(file, formatted) {
+                eprintln!("File write error");
+                eprintln!("{}", e);
+                std::process::exit(define::EXIT_RUNTIME_ERROR);
+            }
+        }
+    }
+    if unformatted {
+        std::process::exit(define::EXIT_CHECK_FAILED);
+    }
+}
+
+/// Prints lint warnings of files and exits with error status if any
+fn lint(matches: &ArgMatches) {
+    let files: Vec<&str> = match matches.values_of("FILE") {
+        Some(values) => values.collect(),
+        None => vec!["-"],
+    };
+    let mut warned = false;
+    for file in files {
+        let mut buffer = read_source(file);
+        buffer.push('\n');
+        let source_code = preprocess(&buffer);
+        let program = parse(&source_code);
+        for warning in lint::lint(&source_code, &program) {
+            println!("{}:{}:{}: warning: {}", file, warning.line, warning.column, warning.message);
+            warned = true;
+        }
+    }
+    if warned {
+        std::process::exit(define::EXIT_CHECK_FAILED);
+    }
+}
+
+/// Parses files and checks types with `--types`, and exits with error status if any error
+fn check(matches: &ArgMatches) {
+    let files: Vec<&str> = match matches.values_of("FILE") {
+        Some(values) => values.collect(),
+        None => vec!["-"],
+    };
+    let mut failed = false;
+    for file in files {
+        let mut buffer = read_source(file);
+        buffer.push('\n');
+        let source_code = preprocess(&buffer);
+        let program = parse(&source_code);
+        if matches.is_present("types") {
+            for error in typecheck::check(&source_code, &program) {
+                println!("{}:{}:{}: error: {}", file, error.line, error.column, error.message);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        std::process::exit(define::EXIT_CHECK_FAILED);
+    }
+}
+
+/// Runs each test function in a fresh interpreter and prints summary
+fn test(matches: &ArgMatches) {
+    let paths: Vec<&str> = match matches.values_of("PATH") {
+        Some(values) => values.collect(),
+        None => vec!["."],
+    };
+    let files = match testing::discover(&paths) {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(define::EXIT_FILE_READ_ERROR);
+        },
+    };
+    let mut passed = 0;
+    let mut failures = Vec::new();
+    for path in files {
+        let file = path.display().to_string();
+        let mut buffer = match fs::read_to_string(&path) {
+            Ok(buffer) => buffer,
+            Err(e) => {
+                println!("test {} ... FAILED", file);
+                failures.push((file, e.to_string()));
+                continue;
+            },
+        };
+        buffer.push('\n');
+        let source_code = match preprocessor::preprocess(&buffer) {
+            Ok(source_code) => source_code,
+            Err(e) => {
+                println!("test {} ... FAILED", file);
+                failures.push((file, format!("Preprocess error\n{}", e)));
+                continue;
+            },
+        };
+        let program = match parser::program_all_consuming(&source_code) {
+            Ok(program) => program.1,
+            Err(error) => {
+                let message = match error {
+                    nom::Err::Error(e) => format!("Parse error\n{}", nom::error::convert_error(source_code.as_str(), e)),
+                    _ => "Parse error".to_owned(),
+                };
+                println!("test {} ... FAILED", file);
+                failures.push((file, message));
+                continue;
+            },
+        };
+        for name in testing::test_functions(&program) {
+            let test = format!("{}::{}", file, name);
+            match testing::run_test(&file, &source_code, &program, &name) {
+                Ok(_) => {
+                    println!("test {} ... ok", test);
+                    passed += 1;
+                },
+                Err(e) => {
+                    println!("test {} ... FAILED", test);
+                    failures.push((test, e));
+                },
+            }
+        }
+    }
+    if !failures.is_empty() {
+        println!("\nfailures:");
+        for (test, message) in &failures {
+            println!("\n---- {} ----\n{}", test, message);
+        }
+    }
+    let result = if failures.is_empty() { "ok" } else { "FAILED" };
+    println!("\ntest result: {}. {} passed; {} failed", result, passed, failures.len());
+    if !failures.is_empty() {
+        std::process::exit(define::EXIT_TEST_FAILED);
     }
 }
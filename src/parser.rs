@@ -21,10 +21,7 @@ use nom::{
     number::complete::{
         double,
     },
-    branch::{
-        alt,
-        permutation,
-    },
+    branch::alt,
     combinator::{
         opt,
         map,
@@ -35,8 +32,14 @@ use nom::{
         many0,
         many1,
     },
-    sequence::delimited,
-    error::VerboseError,
+    sequence::{
+        delimited,
+        tuple,
+    },
+    error::{
+        VerboseError,
+        VerboseErrorKind,
+    },
 };
 use std::char::{
     decode_utf16,
@@ -44,6 +47,37 @@ use std::char::{
 };
 use std::u16;
 
+/// Position of a node in parsed source
+///
+/// Parsers only see the rest of input, so span holds length of the rest
+/// and `line_column` converts it to a position with the whole source.
+/// Nodes read from AST have no source and hold their position instead.
+/// Spans are ignored when nodes are compared.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Span {
+    pub rest: usize,
+    /// 1-based line and column of node read from AST
+    pub position: Option<(usize, usize)>,
+}
+impl Span {
+    /// Returns 1-based line and column of span in `source`
+    pub fn line_column(&self, source: &str) -> (usize, usize) {
+        if let Some(position) = self.position {
+            return position
+        }
+        let offset = source.len().saturating_sub(self.rest);
+        let before = source.get(..offset).unwrap_or(source);
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+        (line, column)
+    }
+}
+impl PartialEq for Span {
+    fn eq(&self, _: &Span) -> bool {
+        true
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Factor {
     pub identifier: Option<String>,
@@ -52,6 +86,7 @@ pub struct Factor {
     pub float: Option<f64>,
     pub expression: Option<Expression>,
     pub block: Option<Block>,
+    pub span: Span,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -62,6 +97,7 @@ pub struct Expression {
 #[derive(Debug, PartialEq, Clone)]
 pub struct Statement {
     pub expression: Expression,
+    pub span: Span,
     //pub params: Vec<Factor>,
 }
 
@@ -77,8 +113,43 @@ pub struct Block {
 
 
 pub fn program_all_consuming(s: &str) -> IResult<&str, Program, VerboseError<&str>> {
+    if let Some(rest) = nesting_too_deep(s) {
+        return Err(nom::Err::Error(VerboseError { errors: vec![(rest, VerboseErrorKind::Context(define::NESTING_TOO_DEEP))] }))
+    }
     all_consuming(program)(s)
 }
+/// Returns `s` from the first bracket nested deeper than `MAX_NESTING_DEPTH`
+///
+/// Parser and evaluator recurse on nesting, so deeper input would overflow the stack.
+fn nesting_too_deep(s: &str) -> Option<&str> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (index, c) in s.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '(' | '{' | '[' => {
+                depth += 1;
+                if define::MAX_NESTING_DEPTH < depth {
+                    return Some(&s[index..])
+                }
+            },
+            ')' | '}' | ']' => depth = depth.saturating_sub(1),
+            _ => {},
+        }
+    }
+    None
+}
 pub fn program(s: &str) -> IResult<&str, Program, VerboseError<&str>> {
     map(
         many1(
@@ -116,14 +187,14 @@ pub fn statement_all_consuming(s: &str) -> IResult<&str, Statement, VerboseError
 }
 pub fn statement(s: &str) -> IResult<&str, Statement, VerboseError<&str>> {
     map(
-        permutation((
+        tuple((
             multispace0,
             expression,
             space0,
             line_ending,
         )),
         |expr| {
-            Statement{ expression: expr.1 }
+            Statement{ span: expr.1.factors[0].span, expression: expr.1 }
         }
     )(s)
 }
@@ -133,10 +204,10 @@ pub fn expression_all_consuming(s: &str) -> IResult<&str, Expression, VerboseErr
 }
 pub fn expression(s: &str) -> IResult<&str, Expression, VerboseError<&str>> {
     map(
-        permutation((
+        tuple((
             factor,
             many0(
-                permutation((
+                tuple((
                     space1,
                     factor,
                 ))
@@ -154,16 +225,16 @@ pub fn expression(s: &str) -> IResult<&str, Expression, VerboseError<&str>> {
 }
 
 pub fn factor(s: &str) -> IResult<&str, Factor, VerboseError<&str>> {
-    alt((
+    let (rest, mut factor) = alt((
         string,
         number,
         map(
-            permutation((
+            tuple((
                 identifier,
                 opt(
                     delimited(
                         tag(define::INDEX_OPEN),
-                        permutation((
+                        tuple((
                             space0,
                             expression,
                             space0,
@@ -199,7 +270,8 @@ pub fn factor(s: &str) -> IResult<&str, Factor, VerboseError<&str>> {
                         int: None,
                         float: None,
                         expression: Some(e),
-                        block: None
+                        block: None,
+                        span: Span::default(),
                     },
                     None => Factor {
                         identifier: None,
@@ -208,6 +280,7 @@ pub fn factor(s: &str) -> IResult<&str, Factor, VerboseError<&str>> {
                         float: None,
                         expression: Some(Expression { factors: Vec::new() }),
                         block: None,
+                        span: Span::default(),
                     },
                 }
             }
@@ -222,17 +295,20 @@ pub fn factor(s: &str) -> IResult<&str, Factor, VerboseError<&str>> {
                     float: None,
                     expression: None,
                     block: Some(block),
+                    span: Span::default(),
                 }
             }
         ),
-    ))(s)
+    ))(s)?;
+    factor.span = Span { rest: s.len(), position: None };
+    Ok((rest, factor))
 }
 
 pub fn identifier(s: &str) -> IResult<&str, Factor, VerboseError<&str>> {
     map(
         is_not(define::PARSER_NOT_IDENTIFIER),
         |identifier: &str| -> Factor {
-            Factor { identifier: Some(identifier.to_owned()), string: None, int: None, float: None, expression: None, block: None }
+            Factor { identifier: Some(identifier.to_owned()), string: None, int: None, float: None, expression: None, block: None, span: Span::default() }
         }
     )(s)
 }
@@ -240,7 +316,7 @@ pub fn number(s: &str) -> IResult<&str, Factor, VerboseError<&str>> {
     map(
         double,
         |number: f64| -> Factor {
-            Factor { identifier: None, string: None, int: None, float: Some(number), expression: None, block: None }
+            Factor { identifier: None, string: None, int: None, float: Some(number), expression: None, block: None, span: Span::default() }
         }
     )(s)
 }
@@ -262,27 +338,30 @@ pub fn string_content(s: &str) -> IResult<&str, Factor, VerboseError<&str>> {
                 value('\n', char('n')),
                 value('\t', char('t')),
                 map(
-                    permutation((char('u'), take_while_m_n(4, 4, |c: char| c.is_ascii_hexdigit()))),
+                    tuple((char('u'), take_while_m_n(4, 4, |c: char| c.is_ascii_hexdigit()))),
                     |(_, code): (char, &str)| -> char {
-                        decode_utf16(vec![u16::from_str_radix(code, 16).unwrap()]).nth(0).unwrap().unwrap_or(REPLACEMENT_CHARACTER)
+                        u16::from_str_radix(code, 16).ok()
+                            .and_then(|code| decode_utf16(vec![code]).next())
+                            .and_then(|c| c.ok())
+                            .unwrap_or(REPLACEMENT_CHARACTER)
                     },
                 )
             ))),
             char('"'),
         ),
         |string: String| -> Factor {
-            Factor { identifier: None, string: Some(string), int: None, float: None, expression: None, block: None }
+            Factor { identifier: None, string: Some(string), int: None, float: None, expression: None, block: None, span: Span::default() }
         }
     )(s)
 }
 pub fn string_empty(s: &str) -> IResult<&str, Factor, VerboseError<&str>> {
     map(
-        permutation((
+        tuple((
             char('"'),
             char('"'),
         )),
         |(_, _)| -> Factor {
-            Factor { identifier: None, string: Some("".to_owned()), int: None, float: None, expression: None, block: None }
+            Factor { identifier: None, string: Some("".to_owned()), int: None, float: None, expression: None, block: None, span: Span::default() }
         }
     )(s)
 }
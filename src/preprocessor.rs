@@ -2,6 +2,7 @@ use nom::{
     IResult,
     character::complete::{
         none_of,
+        one_of,
         char,
         line_ending,
         not_line_ending,
@@ -11,10 +12,7 @@ use nom::{
         is_not,
         escaped,
     },
-    branch::{
-        alt,
-        permutation,
-    },
+    branch::alt,
     combinator::{
         map,
         all_consuming,
@@ -22,7 +20,10 @@ use nom::{
     multi::{
         many0,
     },
-    sequence::delimited,
+    sequence::{
+        delimited,
+        tuple,
+    },
     error::{
         VerboseError,
         convert_error,
@@ -48,6 +49,37 @@ pub fn preprocess(s: &str) -> Result<String, String> {
         },
     }
 }
+/// Returns true if `s` ends inside a string or has unclosed brackets
+///
+/// REPL uses this to distinguish input to be continued from syntax errors.
+pub fn is_incomplete(s: &str) -> bool {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut in_comment = false;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if in_comment {
+            if c == '\n' {
+                in_comment = false;
+            }
+        } else if in_string {
+            if c == '\\' {
+                chars.next();
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else {
+            match c {
+                '"' => in_string = true,
+                '#' => in_comment = true,
+                '(' | '{' | '[' => depth += 1,
+                ')' | '}' | ']' => depth -= 1,
+                _ => {},
+            }
+        }
+    }
+    in_string || 0 < depth
+}
 pub fn source_code_all_consuming(s: &str) -> IResult<&str, String, VerboseError<&str>> {
     all_consuming(source_code)(s)
 }
@@ -71,7 +103,7 @@ pub fn source_code(s: &str) -> IResult<&str, String, VerboseError<&str>> {
 }
 pub fn comment(s: &str) -> IResult<&str, String, VerboseError<&str>> {
     map(
-        permutation((
+        tuple((
             tag("#"),
             map(
                 not_line_ending,
@@ -99,7 +131,7 @@ pub fn string_content(s: &str) -> IResult<&str, String, VerboseError<&str>> {
             escaped(
                 none_of("\"\\"),
                 '\\',
-                tag("\""),
+                one_of("\"\\'rntu"),
             ),
             char('"'),
         ),
@@ -110,7 +142,7 @@ pub fn string_content(s: &str) -> IResult<&str, String, VerboseError<&str>> {
 }
 pub fn string_empty(s: &str) -> IResult<&str, String, VerboseError<&str>> {
     map(
-        permutation((
+        tuple((
             char('"'),
             char('"'),
         )),
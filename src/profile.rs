@@ -0,0 +1,179 @@
+use crate::silang::{
+    Interpreter,
+    Value,
+    EvalReturn,
+};
+use crate::run::Expression;
+use crate::parser;
+
+use std::collections::HashMap;
+use std::time::{
+    Duration,
+    Instant,
+};
+
+static BUILTIN: &str = "builtin";
+static USER_DEFINED: &str = "function";
+static STATEMENT_TEXT_LENGTH: usize = 40;
+
+#[derive(Debug, Default, Clone)]
+pub struct FunctionProfile {
+    pub builtin: bool,
+    pub calls: u64,
+    /// Time until return, counted once for recursive calls
+    pub inclusive: Duration,
+    /// Inclusive time minus time of called functions
+    pub exclusive: Duration,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct StatementProfile {
+    pub span: parser::Span,
+    pub hits: u64,
+    /// Time until done, counted once for recursive execution
+    pub time: Duration,
+}
+
+struct Call {
+    name: String,
+    start: Instant,
+    children: Duration,
+}
+
+/// Records calls and statements while running
+#[derive(Default)]
+pub struct Profiler {
+    pub functions: HashMap<String, FunctionProfile>,
+    /// Keyed by source file index and span of statement
+    pub statements: HashMap<(Option<usize>, usize), StatementProfile>,
+    /// Exclusive time keyed by `;` separated call stack
+    pub stacks: HashMap<String, Duration>,
+    calls: Vec<Call>,
+    running_statements: Vec<(Option<usize>, usize)>,
+}
+impl Profiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn enter(&mut self, name: String) {
+        self.calls.push(Call { name, start: Instant::now(), children: Duration::ZERO });
+    }
+    fn leave(&mut self, builtin: bool) {
+        let call = match self.calls.pop() {
+            Some(call) => call,
+            None => return,
+        };
+        let inclusive = call.start.elapsed();
+        let exclusive = inclusive.saturating_sub(call.children);
+        if let Some(parent) = self.calls.last_mut() {
+            parent.children += inclusive;
+        }
+        let recursive = self.calls.iter().any(|c| c.name == call.name);
+        let mut stack: Vec<&str> = self.calls.iter().map(|c| c.name.as_str()).collect();
+        stack.push(&call.name);
+        *self.stacks.entry(stack.join(";")).or_default() += exclusive;
+        let function = self.functions.entry(call.name).or_default();
+        function.builtin = builtin;
+        function.calls += 1;
+        function.exclusive += exclusive;
+        if !recursive {
+            function.inclusive += inclusive;
+        }
+    }
+
+    /// Returns stacks in folded format with exclusive microseconds as counts
+    pub fn folded_stacks(&self) -> String {
+        let mut stacks: Vec<_> = self.stacks.iter().collect();
+        stacks.sort();
+        let mut buffer = String::new();
+        for (stack, time) in stacks {
+            buffer.push_str(&format!("{} {}\n", stack, time.as_micros()));
+        }
+        buffer
+    }
+}
+
+impl Interpreter {
+    /// Calls function like `call` and records its time
+    pub fn profile_call(&mut self, expr: &Expression, values: Vec<Value>) -> Result<EvalReturn, String> {
+        let builtin = values[0].function.is_some();
+        if let Some(profiler) = &mut self.profiler {
+            profiler.enter(expr.function_name());
+        }
+        let result = if self.tracer.is_some() {
+            self.trace_call(expr, values)
+        } else {
+            self.call(expr, values)
+        };
+        if let Some(profiler) = &mut self.profiler {
+            profiler.leave(builtin);
+        }
+        result
+    }
+
+    /// Evaluates `statement` like `exec` and records its hit and time
+    pub fn profile_statement(&mut self, statement: &parser::Statement) -> Result<EvalReturn, String> {
+        let key = (self.current_source, statement.span.rest);
+        if let Some(profiler) = &mut self.profiler {
+            profiler.running_statements.push(key);
+        }
+        let start = Instant::now();
+        let expression = self.parser_expr_to_run_expr(&statement.expression);
+        let result = self.eval(&expression, true);
+        let time = start.elapsed();
+        if let Some(profiler) = &mut self.profiler {
+            profiler.running_statements.pop();
+            let recursive = profiler.running_statements.contains(&key);
+            let profile = profiler.statements.entry(key).or_default();
+            profile.span = statement.span;
+            profile.hits += 1;
+            if !recursive {
+                profile.time += time;
+            }
+        }
+        result
+    }
+
+    /// Returns tables of functions sorted by exclusive time and statements sorted by time
+    pub fn profile_summary(&self, profiler: &Profiler) -> String {
+        let mut functions: Vec<_> = profiler.functions.iter().collect();
+        functions.sort_by(|a, b| b.1.exclusive.cmp(&a.1.exclusive).then(a.0.cmp(b.0)));
+        let mut buffer = format!("{:>10} {:>14} {:>14} {:<8} {}\n", "calls", "inclusive(ms)", "exclusive(ms)", "kind", "function");
+        for (name, function) in functions {
+            buffer.push_str(&format!("{:>10} {:>14.3} {:>14.3} {:<8} {}\n",
+                function.calls,
+                milliseconds(function.inclusive),
+                milliseconds(function.exclusive),
+                if function.builtin { BUILTIN } else { USER_DEFINED },
+                name,
+            ));
+        }
+        let mut statements: Vec<_> = profiler.statements.iter()
+            .map(|((source, _), profile)| (self.statement_location(*source, profile.span), profile))
+            .collect();
+        statements.sort_by(|a, b| b.1.time.cmp(&a.1.time).then(a.0.cmp(&b.0)));
+        buffer.push_str(&format!("\n{:>10} {:>14} {}\n", "hits", "time(ms)", "statement"));
+        for ((file, line, column, text), profile) in statements {
+            buffer.push_str(&format!("{:>10} {:>14.3} {}:{}:{}: {}\n", profile.hits, milliseconds(profile.time), file, line, column, text));
+        }
+        buffer
+    }
+
+    /// Returns file name, line, column and beginning of the statement at `span`
+    fn statement_location(&self, source: Option<usize>, span: parser::Span) -> (String, usize, usize, String) {
+        let file = match source.and_then(|index| self.source_files.get(index)) {
+            Some(file) => file,
+            None => return (String::new(), 0, 0, String::new()),
+        };
+        let (line, column) = span.line_column(&file.source);
+        let start = file.source.len().saturating_sub(span.rest);
+        let text: String = file.source.get(start..).unwrap_or("").lines().next().unwrap_or("")
+            .trim().chars().take(STATEMENT_TEXT_LENGTH).collect();
+        (file.name.clone(), line, column, text)
+    }
+}
+
+fn milliseconds(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
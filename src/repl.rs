@@ -0,0 +1,314 @@
+use crate::silang::{
+    Interpreter,
+    Value,
+};
+use crate::parser;
+use crate::preprocessor;
+use crate::define;
+
+use std::fs;
+
+use rustyline::{
+    Editor,
+    Helper,
+    Config,
+    CompletionType,
+    history::DefaultHistory,
+    completion::{
+        Completer,
+        Pair,
+    },
+    hint::Hinter,
+    highlight::Highlighter,
+    validate::Validator,
+    error::ReadlineError,
+};
+
+static PROMPT: &str = "> ";
+static CONTINUATION_PROMPT: &str = ". ";
+static HISTORY_FILE: &str = ".silang_history";
+
+static COMMANDS: [&str; 6] = [":help", ":vars", ":type", ":load", ":reset", ":tree"];
+static HELP: &str = "\
+:help         Show this help
+:vars         List variables
+:type expr    Print types of values of expr
+:load file    Run file in current context
+:reset        Reset context
+:tree expr    Print parse tree of expr";
+
+pub fn run(interpreter: &mut Interpreter, args: &[String], parse_tree: bool) {
+    println!("SILang Interpreter Ver:{}", interpreter.version);
+    let config = Config::builder()
+        .completion_type(CompletionType::List)
+        .build();
+    let mut editor: Editor<ReplHelper, DefaultHistory> = match Editor::with_config(config) {
+        Ok(editor) => editor,
+        Err(e) => {
+            eprintln!("{}", e);
+            return
+        },
+    };
+    editor.set_helper(Some(ReplHelper { candidates: Vec::new() }));
+    let history = history_path();
+    if let Some(path) = &history {
+        editor.load_history(path).ok();
+    }
+
+    let mut buffer = String::new();
+    loop {
+        let prompt = if buffer.is_empty() { PROMPT } else { CONTINUATION_PROMPT };
+        if let Some(helper) = editor.helper_mut() {
+            helper.candidates = completion_candidates(interpreter);
+        }
+        let line = match editor.readline(prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => {
+                buffer = String::new();
+                continue;
+            },
+            Err(_) => break,
+        };
+        if !line.trim().is_empty() {
+            editor.add_history_entry(line.as_str()).ok();
+        }
+        if buffer.is_empty() && line.starts_with(':') {
+            command(interpreter, args, &line);
+            continue;
+        }
+        buffer.push_str(&line);
+        buffer.push('\n');
+        if preprocessor::is_incomplete(&buffer) {
+            continue;
+        }
+        let source_code = match preprocessor::preprocess(&buffer) {
+            Ok(source_code) => source_code,
+            Err(e) => {
+                eprintln!("Preprocess error\n{}", e);
+                buffer = String::new();
+                continue;
+            },
+        };
+        buffer = String::new();
+        if source_code.trim().is_empty() {
+            continue;
+        }
+        let program = match parser::program_all_consuming(&source_code) {
+            Ok(program) => program.1,
+            Err(error) => {
+                eprintln!("Parse error");
+                if let nom::Err::Error(e) = error {
+                    eprintln!("{}", nom::error::convert_error(source_code.as_str(), e));
+                }
+                continue;
+            },
+        };
+        if parse_tree {
+            println!("{}", parser::parse_tree_program(&program, 0));
+            continue;
+        }
+        for statement in &program.statements {
+            match interpreter.exec(statement) {
+                Ok(result) => {
+                    for v in result.values {
+                        interpreter.print_value(&v).ok();
+                        print!(" ");
+                    }
+                    println!();
+                },
+                Err(e) => {
+                    eprintln!("{}", e);
+                    break;
+                },
+            }
+            if let Some(status) = interpreter.exit_status {
+                save_history(&mut editor, &history);
+                std::process::exit(status);
+            }
+        }
+    }
+    save_history(&mut editor, &history);
+}
+
+fn history_path() -> Option<std::path::PathBuf> {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+    Some(std::path::Path::new(&home).join(HISTORY_FILE))
+}
+fn save_history(editor: &mut Editor<ReplHelper, DefaultHistory>, history: &Option<std::path::PathBuf>) {
+    if let Some(path) = history {
+        editor.save_history(path).ok();
+    }
+}
+
+/// Completes identifiers visible from current scope and meta commands
+struct ReplHelper {
+    candidates: Vec<Pair>,
+}
+impl Completer for ReplHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _: &rustyline::Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let start = match line[..pos].rfind(|c| define::PARSER_NOT_IDENTIFIER.contains(c)) {
+            Some(index) => index + 1,
+            None => 0,
+        };
+        let word = &line[start..pos];
+        let mut candidates = Vec::new();
+        if start == 0 && word.starts_with(':') {
+            for command in COMMANDS.iter().filter(|c| c.starts_with(word)) {
+                candidates.push(Pair { display: command.to_string(), replacement: command.to_string() });
+            }
+        } else {
+            for candidate in self.candidates.iter().filter(|c| c.replacement.starts_with(word)) {
+                candidates.push(Pair { display: candidate.display.clone(), replacement: candidate.replacement.clone() });
+            }
+        }
+        Ok((start, candidates))
+    }
+}
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+impl Highlighter for ReplHelper {}
+impl Validator for ReplHelper {}
+impl Helper for ReplHelper {}
+
+/// Returns visible identifiers with parameter lists of user defined functions
+fn completion_candidates(interpreter: &Interpreter) -> Vec<Pair> {
+    let mut candidates = Vec::new();
+    for (name, id) in interpreter.context.visible_identifiers() {
+        let value = interpreter.context.get_value_from_identifier_id(id);
+        let display = match &value.user_defined_function {
+            Some(udf) => {
+                let args: Vec<_> = udf.args.values.iter()
+                    .filter_map(|v| v.identifier.clone())
+                    .collect();
+                format!("{} ({})", name, args.join(" "))
+            },
+            None => name.clone(),
+        };
+        candidates.push(Pair { display, replacement: name });
+    }
+    candidates
+}
+
+/// Runs REPL meta command `line` like `:type expr`
+fn command(interpreter: &mut Interpreter, args: &[String], line: &str) {
+    let line = line.trim();
+    let (name, argument) = match line.find(char::is_whitespace) {
+        Some(index) => (&line[..index], line[index..].trim()),
+        None => (line, ""),
+    };
+    match name {
+        ":help" => println!("{}", HELP),
+        ":vars" => vars(interpreter),
+        ":type" => type_of(interpreter, argument),
+        ":load" => load(interpreter, argument),
+        ":reset" => {
+            *interpreter = Interpreter::new();
+            interpreter.set_args(args);
+        },
+        ":tree" => {
+            match parser::expression_all_consuming(argument) {
+                Ok(expr) => print!("{}", parser::parse_tree_expression(&expr.1, 0)),
+                Err(_) => eprintln!("Parse error"),
+            }
+        },
+        _ => eprintln!("Unknown command: {}\nType :help for help", name),
+    }
+}
+
+fn vars(interpreter: &mut Interpreter) {
+    let mut bindings = Vec::new();
+    for scope in &interpreter.context.scope {
+        if scope.scope_number == 0 {
+            continue;
+        }
+        let mut names: Vec<_> = interpreter.context.identifier_index[scope.scope_number].iter().collect();
+        names.sort();
+        for (name, id) in names {
+            bindings.push((name.clone(), *id));
+        }
+    }
+    for (name, id) in bindings {
+        let value = interpreter.context.get_value_from_identifier_id(id).clone();
+        print!("{}: {} = ", name, type_name(&value));
+        if value.user_defined_function.is_some() {
+            print!("{}", define::FUNCTION);
+        } else if interpreter.print_value(&value).is_err() {
+            print!("{}", define::VOID);
+        }
+        println!();
+    }
+}
+
+fn type_of(interpreter: &mut Interpreter, argument: &str) {
+    let expr = match parser::expression_all_consuming(argument) {
+        Ok(expr) => interpreter.parser_expr_to_run_expr(&expr.1),
+        Err(_) => {
+            eprintln!("Parse error");
+            return
+        },
+    };
+    match interpreter.eval(&expr, true) {
+        Ok(result) => {
+            let names: Vec<_> = result.values.iter().map(type_name).collect();
+            println!("{}", names.join(" "));
+        },
+        Err(e) => eprintln!("{}", e),
+    }
+}
+
+fn load(interpreter: &mut Interpreter, file: &str) {
+    let mut buffer = match fs::read_to_string(file) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("File read error\n{}", e);
+            return
+        },
+    };
+    buffer.push('\n');
+    let source_code = match preprocessor::preprocess(&buffer) {
+        Ok(source_code) => source_code,
+        Err(e) => {
+            eprintln!("Preprocess error\n{}", e);
+            return
+        },
+    };
+    match parser::program_all_consuming(&source_code) {
+        Ok(program) => {
+            if let Err(e) = interpreter.run(&program.1) {
+                eprintln!("{}", e);
+            }
+        },
+        Err(error) => {
+            eprintln!("Parse error");
+            if let nom::Err::Error(e) = error {
+                eprintln!("{}", nom::error::convert_error(source_code.as_str(), e));
+            }
+        },
+    }
+}
+
+/// Returns type name of `value` for display
+fn type_name(value: &Value) -> &'static str {
+    if value.string.is_some() {
+        define::STRING
+    } else if value.int.is_some() {
+        define::INT
+    } else if value.bigint.is_some() {
+        define::BIGINT
+    } else if value.float.is_some() {
+        define::FLOAT
+    } else if value.bool.is_some() {
+        define::BOOL
+    } else if value.vector.is_some() {
+        define::VECTOR
+    } else if value.map.is_some() {
+        define::MAP
+    } else if value.user_defined_function.is_some() || value.function.is_some() {
+        define::FUNCTION
+    } else {
+        define::VOID
+    }
+}
@@ -7,10 +7,21 @@ use crate::silang::{
 };
 
 use super::parser;
+use super::define;
 
 #[derive(Debug, Clone)]
 pub struct Expression {
     pub values: Vec<Value>,
+    pub span: parser::Span,
+}
+impl Expression {
+    /// Returns name of the function called by this expression
+    pub fn function_name(&self) -> String {
+        match self.values.first().and_then(|v| v.identifier.as_ref()) {
+            Some(name) => name.clone(),
+            None => define::LAMBDA.to_owned(),
+        }
+    }
 }
 
 impl Interpreter {
@@ -23,14 +34,49 @@ impl Interpreter {
     }
     pub fn parser_expr_to_run_expr(&self, expr: &parser::Expression) -> Expression {
         let factors = &expr.factors;
-        let mut expression = Expression { values: Vec::new() };
+        let span = match factors.first() {
+            Some(factor) => factor.span,
+            None => parser::Span::default(),
+        };
+        let mut expression = Expression { values: Vec::new(), span };
         for f in factors {
             expression.values.push(self.factor_to_value(&f));
         }
         expression
     }
 
+    /// Counts a statement or loop iteration and fails beyond `step_limit`
+    pub fn step(&mut self) -> Result<(), String> {
+        self.steps += 1;
+        match self.step_limit {
+            Some(limit) if limit < self.steps => Err(define::STEP_LIMIT_EXCEEDED.to_owned()),
+            _ => Ok(()),
+        }
+    }
+    /// Enters nested evaluation and fails beyond `depth_limit`
+    fn enter(&mut self) -> Result<(), String> {
+        if let Some(limit) = self.depth_limit {
+            if limit <= self.depth {
+                return Err(define::DEPTH_LIMIT_EXCEEDED.to_owned())
+            }
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
     pub fn eval_value(&mut self, value: &Value, dereference: bool) -> Result<EvalReturn, String> {
+        self.enter()?;
+        let result = self.eval_value_nested(value, dereference);
+        #[cfg(any(target_family = "unix", target_family = "windows"))]
+        {
+            if self.tracer.is_some() {
+                self.trace_argument(&result);
+            }
+        }
+        self.depth -= 1;
+        result
+    }
+    fn eval_value_nested(&mut self, value: &Value, dereference: bool) -> Result<EvalReturn, String> {
         if let Ok(v) = self.dereference_value(value) {
             if dereference {
                 self.eval_value(&v, dereference)
@@ -56,7 +102,25 @@ impl Interpreter {
             })
         }
     }
+    /// Evaluates `value` like `eval_value` but runs a block in a scope of `scope_type`
+    pub fn eval_body(&mut self, value: &Value, scope_type: ScopeType) -> Result<EvalReturn, String> {
+        match &value.block {
+            Some(block) if !value.is_reference() => {
+                self.context.push_new(scope_type, false);
+                let result = self.exec_block(block);
+                self.context.pop();
+                result
+            },
+            _ => self.eval_value(value, true),
+        }
+    }
     pub fn eval(&mut self, expr: &Expression, dereference: bool) -> Result<EvalReturn, String> {
+        self.enter()?;
+        let result = self.eval_nested(expr, dereference);
+        self.depth -= 1;
+        result
+    }
+    fn eval_nested(&mut self, expr: &Expression, dereference: bool) -> Result<EvalReturn, String> {
         let mut values = Vec::new();
         if expr.values.len() == 0 {
             return Ok(
@@ -80,11 +144,45 @@ impl Interpreter {
             )
         }
 
+        if values[0].function.is_some() || values[0].user_defined_function.is_some() {
+            #[cfg(any(target_family = "unix", target_family = "windows"))]
+            {
+                if self.profiler.is_some() {
+                    return self.profile_call(expr, values)
+                }
+                if self.tracer.is_some() {
+                    return self.trace_call(expr, values)
+                }
+            }
+            self.call(expr, values)
+        } else {
+            for v in &expr.values[1..] {
+                match self.eval_value(v, dereference) {
+                    Ok(r) => {
+                        for i in r.values {
+                            values.push(i);
+                        }
+                    },
+                    Err(e) => return Err(e),
+                }
+            }
+
+            return Ok(
+                EvalReturn {
+                    result: EvalResult::Normal,
+                    values: values,
+                }
+            )
+        }
+    }
+    /// Calls builtin or user defined function `values[0]` with arguments of `expr`
+    pub fn call(&mut self, expr: &Expression, mut values: Vec<Value>) -> Result<EvalReturn, String> {
         if let Some(func) = values[0].function {
             for v in &expr.values[1..] {
                 values.push(v.clone())
             }
 
+            self.current_span = expr.span;
             func(self, &values)
         } else if let Some(udf) = values[0].user_defined_function.clone() {
             let mut args = Vec::new();
@@ -98,38 +196,55 @@ impl Interpreter {
             let mut args_lhs = Value::new();
             args_lhs.expression = Some(udf.args);
             let mut args_rhs = Value::new();
-            args_rhs.expression = Some(Expression { values: args });
-            match self.assign_variable(&args_lhs, &args_rhs, false) {
-                Ok(_) => {},
-                Err(e) => return Err(e),
-            }
-            let res = self.exec_block(&udf.block);
+            args_rhs.expression = Some(Expression { values: args, span: expr.span });
+            let res = match self.assign_variable(&args_lhs, &args_rhs, false) {
+                Ok(bound) => {
+                    #[cfg(any(target_family = "unix", target_family = "windows"))]
+                    {
+                        if let (Some(_), Some(params)) = (&self.tracer, &args_lhs.expression) {
+                            self.trace_arguments(params, &bound);
+                        }
+                    }
+                    let current_source = self.current_source;
+                    self.current_source = udf.source;
+                    self.call_stack.push(expr.function_name());
+                    let res = self.exec_block(&udf.block);
+                    self.call_stack.pop();
+                    self.current_source = current_source;
+                    res
+                },
+                Err(e) => Err(e),
+            };
             self.context.pop();
             self.context.scope = backup_scope;
-            return res
+            res
         } else {
-            for v in &expr.values[1..] {
-                match self.eval_value(v, dereference) {
-                    Ok(r) => {
-                        for i in r.values {
-                            values.push(i);
-                        }
-                    },
-                    Err(e) => return Err(e),
-                }
-            }
-
-            return Ok(
-                EvalReturn {
-                    result: EvalResult::Normal,
-                    values: values,
-                }
-            )
+            Err("Invalid function call".to_owned())
         }
     }
 
-
     pub fn exec(&mut self, statement: &parser::Statement) -> Result<EvalReturn, String> {
+        self.step()?;
+        #[cfg(any(target_family = "unix", target_family = "windows"))]
+        {
+            if self.debugger.is_some() {
+                self.debug_statement(statement);
+                if self.exit_status.is_some() {
+                    return Ok(
+                        EvalReturn {
+                            result: EvalResult::Normal,
+                            values: Vec::new(),
+                        }
+                    )
+                }
+            }
+            if self.coverage.is_some() {
+                self.cover_statement(statement);
+            }
+            if self.profiler.is_some() {
+                return self.profile_statement(statement)
+            }
+        }
         let expression = self.parser_expr_to_run_expr(&statement.expression);
         self.eval(&expression, true)
     }
@@ -146,6 +261,9 @@ impl Interpreter {
             match self.exec(&s) {
                 Ok(r) => {
                     result = r;
+                    if self.exit_status.is_some() {
+                        break;
+                    }
                     if result.result == EvalResult::Return {
                         if self.context.current_scope().scope_type == ScopeType::UserDefinedFunction {
                             result.result = EvalResult::Normal;
@@ -1,8 +1,18 @@
 use crate::parser;
 use crate::run;
 use crate::define;
+use crate::builtin;
+#[cfg(any(target_family = "unix", target_family = "windows"))]
+use crate::debugger;
+#[cfg(any(target_family = "unix", target_family = "windows"))]
+use crate::trace;
+#[cfg(any(target_family = "unix", target_family = "windows"))]
+use crate::profile;
+#[cfg(any(target_family = "unix", target_family = "windows"))]
+use crate::coverage;
 
 use std::collections::HashMap;
+use num_bigint::BigInt;
 
 #[cfg(any(target_family = "unix", target_family = "windows"))]
 pub struct Interpreter {
@@ -10,6 +20,27 @@ pub struct Interpreter {
     pub version: &'static str,
     pub libraries: Vec<libloading::Library>,
     pub stdout_func: fn (&mut Interpreter, &str),
+    pub stderr_func: fn (&mut Interpreter, &str),
+    pub stdin_func: fn (&mut Interpreter) -> Option<String>,
+    pub stdin_eof: bool,
+    pub file_system_enabled: bool,
+    pub exit_status: Option<i32>,
+    pub source_files: Vec<SourceFile>,
+    pub current_source: Option<usize>,
+    /// Position of the builtin function call being evaluated
+    pub current_span: parser::Span,
+    /// Names of user defined functions being called
+    pub call_stack: Vec<String>,
+    /// Maximum number of statements and loop iterations to run
+    pub step_limit: Option<u64>,
+    pub steps: u64,
+    /// Maximum depth of nested evaluation
+    pub depth_limit: Option<usize>,
+    pub depth: usize,
+    pub debugger: Option<debugger::Debugger>,
+    pub tracer: Option<trace::Tracer>,
+    pub profiler: Option<profile::Profiler>,
+    pub coverage: Option<coverage::Coverage>,
 }
 #[cfg(target_family = "wasm")]
 pub struct Interpreter {
@@ -17,6 +48,30 @@ pub struct Interpreter {
     pub version: &'static str,
     pub stdout_buffer: String,
     pub stdout_func: fn (&mut Interpreter, &str),
+    pub stderr_buffer: String,
+    pub stderr_func: fn (&mut Interpreter, &str),
+    pub stdin_buffer: String,
+    pub stdin_func: fn (&mut Interpreter) -> Option<String>,
+    pub stdin_eof: bool,
+    pub exit_status: Option<i32>,
+    pub source_files: Vec<SourceFile>,
+    pub current_source: Option<usize>,
+    /// Position of the builtin function call being evaluated
+    pub current_span: parser::Span,
+    /// Names of user defined functions being called
+    pub call_stack: Vec<String>,
+    /// Maximum number of statements and loop iterations to run
+    pub step_limit: Option<u64>,
+    pub steps: u64,
+    /// Maximum depth of nested evaluation
+    pub depth_limit: Option<usize>,
+    pub depth: usize,
+}
+
+/// Source code loaded by the interpreter, used to find lines of statements
+pub struct SourceFile {
+    pub name: String,
+    pub source: String,
 }
 
 impl Interpreter {
@@ -27,6 +82,30 @@ impl Interpreter {
             version: define::VERSION,
             libraries: Vec::new(),
             stdout_func: |_, data| print!("{}", data),
+            stderr_func: |_, data| eprint!("{}", data),
+            stdin_func: |_| {
+                std::io::Write::flush(&mut std::io::stdout()).ok();
+                let mut line = String::new();
+                match std::io::stdin().read_line(&mut line) {
+                    Ok(0) | Err(_) => None,
+                    Ok(_) => Some(line),
+                }
+            },
+            stdin_eof: false,
+            file_system_enabled: true,
+            exit_status: None,
+            source_files: Vec::new(),
+            current_source: None,
+            current_span: parser::Span::default(),
+            call_stack: Vec::new(),
+            step_limit: None,
+            steps: 0,
+            depth_limit: Some(define::MAX_EVAL_DEPTH),
+            depth: 0,
+            debugger: None,
+            tracer: None,
+            profiler: None,
+            coverage: None,
         }
     }
     #[cfg(target_family = "wasm")]
@@ -36,6 +115,29 @@ impl Interpreter {
             version: define::VERSION,
             stdout_buffer: String::new(),
             stdout_func: |interpreter, data| interpreter.stdout_buffer.push_str(data),
+            stderr_buffer: String::new(),
+            stderr_func: |interpreter, data| interpreter.stderr_buffer.push_str(data),
+            stdin_buffer: String::new(),
+            stdin_func: |interpreter| {
+                if interpreter.stdin_buffer.is_empty() {
+                    return None
+                }
+                let end = match interpreter.stdin_buffer.find('\n') {
+                    Some(index) => index + 1,
+                    None => interpreter.stdin_buffer.len(),
+                };
+                Some(interpreter.stdin_buffer.drain(..end).collect())
+            },
+            stdin_eof: false,
+            exit_status: None,
+            source_files: Vec::new(),
+            current_source: None,
+            current_span: parser::Span::default(),
+            call_stack: Vec::new(),
+            step_limit: None,
+            steps: 0,
+            depth_limit: Some(define::MAX_EVAL_DEPTH),
+            depth: 0,
         }
     }
 
@@ -45,6 +147,29 @@ impl Interpreter {
         self.stdout_buffer = String::new();
         tmp
     }
+    #[cfg(target_family = "wasm")]
+    pub fn stderr_buffer_flush(&mut self) -> String {
+        let tmp = self.stderr_buffer.clone();
+        self.stderr_buffer = String::new();
+        tmp
+    }
+    /// Appends `data` to the input read by `stdin_func`
+    #[cfg(target_family = "wasm")]
+    pub fn buffer_input(&mut self, data: &str) {
+        self.stdin_buffer.push_str(data);
+    }
+
+    /// Registers source code parsed from file `name` and returns its index
+    pub fn add_source_file(&mut self, name: &str, source: &str) -> usize {
+        self.source_files.push(SourceFile { name: name.to_owned(), source: source.to_owned() });
+        self.source_files.len() - 1
+    }
+    /// Returns file name and 1-based line of `statement` in the running source
+    pub fn statement_position(&self, statement: &parser::Statement) -> Option<(&str, usize)> {
+        let file = self.source_files.get(self.current_source?)?;
+        let (line, _) = statement.span.line_column(&file.source);
+        Some((&file.name, line))
+    }
 
     pub fn factor_to_value(&self, factor: &parser::Factor) -> Value {
         let mut value = Value::new();
@@ -101,6 +226,7 @@ pub struct EvalReturn {
 pub enum SILType {
     String,
     Int,
+    BigInt,
     Float,
     Bool,
     Vector,
@@ -116,6 +242,8 @@ pub struct UserDefinedFunction {
     pub scope: Vec<ScopeInfo>,
     pub args: run::Expression,
     pub block: parser::Block,
+    /// Index of `source_files` the block was parsed from
+    pub source: Option<usize>,
 }
 
 pub type IdentifierRefID = usize;
@@ -126,6 +254,7 @@ pub struct Value {
     pub identifier_id: Option<IdentifierRefID>,
     pub string: Option<String>,
     pub int: Option<i64>,
+    pub bigint: Option<BigInt>,
     pub float: Option<f64>,
     pub bool: Option<bool>,
     pub vector: Option<Vec<Value>>,
@@ -143,6 +272,7 @@ impl Value {
             identifier_id: None,
             string: None,
             int: None,
+            bigint: None,
             float: None,
             bool: None,
             vector: None,
@@ -161,12 +291,13 @@ impl std::fmt::Debug for Value {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
             fmt,
-            "Value {{ {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?} }}",
+            "Value {{ {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?} }}",
             self.sil_type,
             self.identifier,
             self.identifier_id,
             self.string,
             self.int,
+            self.bigint,
             self.float,
             self.bool,
             self.vector,
@@ -234,7 +365,10 @@ impl Context {
         self.identifier_index.pop();
     }
     pub fn current_scope(&self) -> ScopeInfo {
-        self.scope[self.scope.len() - 1].clone()
+        match self.scope.last() {
+            Some(scope) => scope.clone(),
+            None => ScopeInfo { scope_number: 0, scope_type: ScopeType::Root },
+        }
     }
     /// Returns scope and reference to Value
     /// # Arguments
@@ -261,6 +395,20 @@ impl Context {
             n -= 1;
         }
     }
+    /// Returns names visible from current scope in the order of `search_identifier_id`
+    pub fn visible_identifiers(&self) -> Vec<(String, IdentifierRefID)> {
+        let mut identifiers: Vec<(String, IdentifierRefID)> = Vec::new();
+        for scope in self.scope.iter().rev() {
+            let mut names: Vec<_> = self.identifier_index[scope.scope_number].iter().collect();
+            names.sort();
+            for (name, id) in names {
+                if identifiers.iter().all(|(n, _)| n != name) {
+                    identifiers.push((name.clone(), *id));
+                }
+            }
+        }
+        identifiers
+    }
     pub fn get_value_from_identifier_id(&self, id: IdentifierRefID) -> &Value {
         &self.identifier_storage.storage[id]
     }
@@ -312,90 +460,12 @@ impl Context {
     fn init_identifier_storage(&mut self) {
         self.identifier_index.push(HashMap::new());
 
-        // Functions
-        let mut import = Value::new();
-        import.identifier = Some(define::IMPORT.to_owned());
-        import.function = Some(Interpreter::import);
-        self.store_identifier(0, define::IMPORT, import);
-        let mut lambda = Value::new();
-        lambda.identifier = Some(define::LAMBDA.to_owned());
-        lambda.function = Some(Interpreter::lambda);
-        self.store_identifier(0, define::LAMBDA, lambda);
-        let mut return_expr = Value::new();
-        return_expr.identifier = Some(define::RETURN.to_owned());
-        return_expr.function = Some(Interpreter::return_expression);
-        self.store_identifier(0, define::RETURN, return_expr);
-        // control
-        let mut untyped = Value::new();
-        untyped.identifier = Some(define::UNTYPED.to_owned());
-        untyped.function = Some(Interpreter::untyped);
-        self.store_identifier(0, define::UNTYPED, untyped);
-        let mut if_expr = Value::new();
-        if_expr.identifier = Some(define::IF.to_owned());
-        if_expr.function = Some(Interpreter::if_expression);
-        self.store_identifier(0, define::IF, if_expr);
-        let mut loop_expr = Value::new();
-        loop_expr.identifier = Some(define::LOOP.to_owned());
-        loop_expr.function = Some(Interpreter::loop_expression);
-        self.store_identifier(0, define::LOOP, loop_expr);
-        // Declare
-        let mut decas = Value::new();
-        decas.identifier = Some(define::DECAS.to_owned());
-        decas.function = Some(Interpreter::decas);
-        self.store_identifier(0, define::DECAS, decas);
-        let mut decas_alias = Value::new();
-        decas_alias.identifier = Some(define::DECAS_ALIAS.to_owned());
-        decas_alias.function = Some(Interpreter::decas);
-        self.store_identifier(0, define::DECAS_ALIAS, decas_alias);
-        let mut func_def = Value::new();
-        func_def.identifier = Some(define::FUNCTION_DEFINITION.to_owned());
-        func_def.function = Some(Interpreter::define_function);
-        self.store_identifier(0, define::FUNCTION_DEFINITION, func_def);
-        // Assign
-        let mut assign = Value::new();
-        assign.identifier = Some(define::ASSIGN.to_owned());
-        assign.function = Some(Interpreter::assign);
-        self.store_identifier(0, define::ASSIGN, assign);
-        let mut assign_defer = Value::new();
-        assign_defer.identifier = Some(define::ASSIGN_DEFER.to_owned());
-        assign_defer.function = Some(Interpreter::assign_defer);
-        self.store_identifier(0, define::ASSIGN_DEFER, assign_defer);
-        // Print
-        let mut print = Value::new();
-        print.identifier = Some(define::PRINT.to_owned());
-        print.function = Some(Interpreter::print);
-        self.store_identifier(0, define::PRINT, print);
-        let mut println = Value::new();
-        println.identifier = Some(define::PRINTLN.to_owned());
-        println.function = Some(Interpreter::println);
-        self.store_identifier(0, define::PRINTLN, println);
-        // Arithmetic
-        let mut add = Value::new();
-        add.identifier = Some(define::ADD.to_owned());
-        add.function = Some(Interpreter::add);
-        self.store_identifier(0, define::ADD, add);
-        let mut sub = Value::new();
-        sub.identifier = Some(define::SUB.to_owned());
-        sub.function = Some(Interpreter::sub);
-        self.store_identifier(0, define::SUB, sub);
-        let mut mul = Value::new();
-        mul.identifier = Some(define::MUL.to_owned());
-        mul.function = Some(Interpreter::mul);
-        self.store_identifier(0, define::MUL, mul);
-        let mut div = Value::new();
-        div.identifier = Some(define::DIV.to_owned());
-        div.function = Some(Interpreter::div);
-        self.store_identifier(0, define::DIV, div);
-        let mut rem = Value::new();
-        rem.identifier = Some(define::REM.to_owned());
-        rem.function = Some(Interpreter::rem);
-        self.store_identifier(0, define::REM, rem);
-        // Compare
-        let mut equal = Value::new();
-        equal.identifier = Some(define::EQUAL.to_owned());
-        equal.function = Some(Interpreter::equal);
-        self.store_identifier(0, define::EQUAL, equal);
-
+        for builtin in builtin::builtin_functions() {
+            let mut value = Value::new();
+            value.identifier = Some(builtin.name.to_owned());
+            value.function = Some(builtin.function);
+            self.store_identifier(0, builtin.name, value);
+        }
 
         // Type name
         let mut string = Value::new();
@@ -406,6 +476,10 @@ impl Context {
         int.identifier = Some(define::INT.to_owned());
         int.sil_type = SILType::TypeName;
         self.store_identifier(0, define::INT, int);
+        let mut bigint = Value::new();
+        bigint.identifier = Some(define::BIGINT.to_owned());
+        bigint.sil_type = SILType::TypeName;
+        self.store_identifier(0, define::BIGINT, bigint);
         let mut float = Value::new();
         float.identifier = Some(define::FLOAT.to_owned());
         float.sil_type = SILType::TypeName;
@@ -414,6 +488,10 @@ impl Context {
         bool_type.identifier = Some(define::BOOL.to_owned());
         bool_type.sil_type = SILType::TypeName;
         self.store_identifier(0, define::BOOL, bool_type);
+        let mut vector = Value::new();
+        vector.identifier = Some(define::VECTOR.to_owned());
+        vector.sil_type = SILType::TypeName;
+        self.store_identifier(0, define::VECTOR, vector);
         let mut void = Value::new();
         void.identifier = Some(define::VOID.to_owned());
         void.sil_type = SILType::TypeName;
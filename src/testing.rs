@@ -0,0 +1,93 @@
+use crate::silang::Interpreter;
+use crate::parser::{
+    self,
+    Program,
+};
+use crate::define;
+
+use std::fs;
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+static TEST_FILE_SUFFIX: &str = "_test.sil";
+static TEST_FUNCTION_PREFIX: &str = "test_";
+
+/// Returns test files in `paths` sorted
+///
+/// Directories are searched recursively for `*_test.sil` files
+/// skipping hidden ones, and files are taken as given.
+pub fn discover(paths: &[&str]) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    for path in paths {
+        let path = Path::new(path);
+        if path.is_dir() {
+            discover_dir(path, &mut files)?;
+        } else if path.exists() {
+            files.push(path.to_path_buf());
+        } else {
+            return Err(format!("{}: No such file or directory", path.display()))
+        }
+    }
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+fn discover_dir(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => return Err(format!("{}: {}", dir.display(), e)),
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            discover_dir(&path, files)?;
+        } else if name.ends_with(TEST_FILE_SUFFIX) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Returns names of `test_*` functions defined by `f:` at top level of `program`
+pub fn test_functions(program: &Program) -> Vec<String> {
+    let mut names = Vec::new();
+    for statement in &program.statements {
+        let factors = &statement.expression.factors;
+        if factors.first().and_then(|f| f.identifier.as_deref()) != Some(define::FUNCTION_DEFINITION) {
+            continue;
+        }
+        if let Some(name) = factors.get(1).and_then(|f| f.identifier.as_ref()) {
+            if name.starts_with(TEST_FUNCTION_PREFIX) {
+                names.push(name.clone());
+            }
+        }
+    }
+    names
+}
+
+/// Runs `program` and then calls function `test` in a fresh interpreter
+///
+/// Fails with the runtime error, or if the program exits with non-zero status.
+pub fn run_test(file: &str, source_code: &str, program: &Program, test: &str) -> Result<(), String> {
+    let mut interpreter = Interpreter::new();
+    interpreter.current_source = Some(interpreter.add_source_file(file, source_code));
+    interpreter.run(program)?;
+    if let Some(status) = interpreter.exit_status {
+        return Err(format!("Exited with status {} before running test", status))
+    }
+    let call = match parser::program_all_consuming(&format!("{}\n", test)) {
+        Ok(call) => call.1,
+        Err(_) => return Err(format!("Invalid test name {}", test)),
+    };
+    interpreter.run(&call)?;
+    match interpreter.exit_status {
+        Some(status) if status != 0 => Err(format!("Exited with status {}", status)),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,179 @@
+use crate::silang::{
+    Interpreter,
+    Value,
+    EvalReturn,
+    SILType,
+};
+use crate::run::Expression;
+use crate::builtin;
+use crate::define;
+
+use std::io::Write;
+
+static INDENT: &str = "  ";
+static BLOCK: &str = "{...}";
+
+/// Logs function calls with their arguments and results
+pub struct Tracer {
+    pub output: Box<dyn Write>,
+    /// Names of functions to log, or every function if empty
+    pub filters: Vec<String>,
+    depth: usize,
+    /// Names of builtin functions which evaluate all arguments
+    evaluating: Vec<&'static str>,
+    /// Depth and name of user defined function call logged after binding arguments
+    binding: Option<(usize, String)>,
+    /// Evaluation depth of arguments, log depth and name of builtin calls evaluating them
+    arguments: Vec<(usize, usize, String)>,
+}
+impl Tracer {
+    pub fn new(output: Box<dyn Write>, filters: Vec<String>) -> Self {
+        let evaluating = builtin::builtin_functions().into_iter().filter(|f| f.evaluates_args).map(|f| f.name).collect();
+        Tracer { output, filters, depth: 0, evaluating, binding: None, arguments: Vec::new() }
+    }
+    fn traces(&self, name: &str) -> bool {
+        self.filters.is_empty() || self.filters.iter().any(|f| f == name)
+    }
+    fn log(&mut self, depth: usize, line: &str) {
+        writeln!(self.output, "{}{}", INDENT.repeat(depth), line).ok();
+    }
+}
+
+impl Interpreter {
+    /// Calls function like `call` and logs the call and its result
+    ///
+    /// Arguments are logged as passed, so expressions which the function
+    /// evaluates itself are shown unevaluated and their calls follow nested.
+    /// Builtin functions which evaluate all arguments also log each value
+    /// when evaluating it, and user defined functions are logged with
+    /// parameters after binding them.
+    pub fn trace_call(&mut self, expr: &Expression, values: Vec<Value>) -> Result<EvalReturn, String> {
+        let name = expr.function_name();
+        let evaluating = values[0].function.is_some()
+            && self.tracer.as_ref().is_some_and(|tracer| tracer.evaluating.contains(&name.as_str()));
+        let argument_depth = self.depth + 1;
+        let (depth, traced) = match &mut self.tracer {
+            Some(tracer) => {
+                tracer.depth += 1;
+                (tracer.depth - 1, tracer.traces(&name))
+            },
+            None => (0, false),
+        };
+        if traced && evaluating {
+            if let Some(tracer) = &mut self.tracer {
+                tracer.arguments.push((argument_depth, depth + 1, name.clone()));
+            }
+        }
+        if traced && values[0].user_defined_function.is_some() {
+            if let Some(tracer) = &mut self.tracer {
+                tracer.binding = Some((depth, name.clone()));
+            }
+        } else if traced {
+            let mut call = vec![name.clone()];
+            for value in &expr.values[1..] {
+                call.push(self.trace_value(value, true));
+            }
+            self.trace_log(depth, &format!("({})", call.join(" ")));
+        }
+        let result = self.call(expr, values);
+        if let Some(tracer) = &mut self.tracer {
+            tracer.depth -= 1;
+            tracer.binding = None;
+            if traced && evaluating {
+                tracer.arguments.pop();
+            }
+        }
+        if traced {
+            let line = match &result {
+                Ok(result) if result.values.is_empty() => format!("{} => {}", name, define::VOID),
+                Ok(result) => {
+                    let values: Vec<String> = result.values.iter().map(|v| self.trace_value(v, true)).collect();
+                    format!("{} => {}", name, values.join(" "))
+                },
+                Err(e) => format!("{} !! {}", name, e),
+            };
+            self.trace_log(depth, &line);
+        }
+        result
+    }
+
+    /// Logs `result` of evaluating an argument of the builtin call being traced
+    ///
+    /// Called at end of `eval_value`, which is at the depth of arguments
+    /// only when the builtin function itself evaluates them.
+    pub fn trace_argument(&mut self, result: &Result<EvalReturn, String>) {
+        let (depth, name) = match self.tracer.as_ref().and_then(|tracer| tracer.arguments.last()) {
+            Some((argument_depth, depth, name)) if *argument_depth == self.depth => (*depth, name.clone()),
+            _ => return,
+        };
+        if let Ok(result) = result {
+            let mut values: Vec<String> = result.values.iter().map(|v| self.trace_value(v, true)).collect();
+            if values.len() != 1 {
+                values = vec![format!("({})", values.join(" "))];
+            }
+            self.trace_log(depth, &format!("{} <- {}", name, values[0]));
+        }
+    }
+
+    /// Logs call of user defined function with `params` bound to `bound` values
+    ///
+    /// Arguments are evaluated each time parameters are used, so expressions
+    /// are still shown unevaluated, while variables are shown as `NAME=VALUE`.
+    pub fn trace_arguments(&mut self, params: &Expression, bound: &[Value]) {
+        let (depth, name) = match self.tracer.as_mut().and_then(|tracer| tracer.binding.take()) {
+            Some(binding) => binding,
+            None => return,
+        };
+        let mut call = vec![name];
+        for (param, value) in params.values.iter().zip(bound) {
+            let argument = match self.dereference_value(value) {
+                Ok(argument) => self.trace_value(&argument, true),
+                Err(_) => self.trace_value(value, true),
+            };
+            call.push(format!("{}={}", param.identifier.clone().unwrap_or_default(), argument));
+        }
+        self.trace_log(depth, &format!("({})", call.join(" ")));
+    }
+
+    fn trace_log(&mut self, depth: usize, line: &str) {
+        if let Some(tracer) = &mut self.tracer {
+            tracer.log(depth, line);
+        }
+    }
+
+    /// Formats `value` without evaluating it
+    ///
+    /// Variables are shown as `NAME=VALUE` if `dereference` is true,
+    /// and by their names in unevaluated expressions.
+    fn trace_value(&mut self, value: &Value, dereference: bool) -> String {
+        if let Some(expr) = &value.expression {
+            let items: Vec<String> = expr.values.iter().map(|v| self.trace_value(v, false)).collect();
+            return format!("({})", items.join(" "))
+        }
+        if value.block.is_some() {
+            return BLOCK.to_owned()
+        }
+        let name = match &value.identifier {
+            Some(identifier) => identifier.clone(),
+            None if value.function.is_some() || value.user_defined_function.is_some() => return define::FUNCTION.to_owned(),
+            None => return self.format_value(value),
+        };
+        if !value.is_reference() {
+            return if is_bare(value) { name } else { self.format_value(value) }
+        }
+        match self.dereference_value(value) {
+            Ok(mut v) if dereference && v.sil_type != SILType::TypeName && v.function.is_none() && v.user_defined_function.is_none() => {
+                v.identifier = None;
+                format!("{}={}", name, self.trace_value(&v, true))
+            },
+            _ => name,
+        }
+    }
+}
+
+/// Returns true if `value` is a name without data such as a type name or an undeclared identifier
+fn is_bare(value: &Value) -> bool {
+    value.string.is_none() && value.int.is_none() && value.bigint.is_none() && value.float.is_none()
+        && value.bool.is_none() && value.vector.is_none() && value.map.is_none()
+        && value.function.is_none() && value.user_defined_function.is_none()
+}
@@ -0,0 +1,406 @@
+use crate::parser::{
+    Program,
+    Expression,
+    Factor,
+    Span,
+};
+use crate::silang::SILType;
+use crate::define;
+
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TypeError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone)]
+enum Binding {
+    Variable(SILType),
+    /// User defined function with return type if declared
+    Function(Option<SILType>),
+    Unknown,
+}
+
+struct Checker<'a> {
+    source: &'a str,
+    scopes: Vec<HashMap<String, Binding>>,
+    /// Declared return types of enclosing function definitions
+    return_types: Vec<Option<SILType>>,
+    errors: Vec<TypeError>,
+}
+
+/// Checks types of typed scopes in `program` ahead of execution
+///
+/// Types come from `decas` declarations, `f:` return types and literals.
+/// Expressions whose types are unknown and `untyped` blocks are not checked.
+/// `source` must be the preprocessed source `program` was parsed from.
+pub fn check(source: &str, program: &Program) -> Vec<TypeError> {
+    let mut checker = Checker {
+        source,
+        scopes: vec![HashMap::new()],
+        return_types: Vec::new(),
+        errors: Vec::new(),
+    };
+    checker.declare(define::TRUE, Binding::Variable(SILType::Bool));
+    checker.declare(define::FALSE, Binding::Variable(SILType::Bool));
+    checker.declare(define::ARGS, Binding::Variable(SILType::Vector));
+    checker.program(program);
+    checker.errors.sort_by_key(|e| (e.line, e.column));
+    checker.errors
+}
+
+/// Returns name of `sil_type` as written in source
+pub fn type_name(sil_type: &SILType) -> &'static str {
+    match sil_type {
+        SILType::String => define::STRING,
+        SILType::Int => define::INT,
+        SILType::BigInt => define::BIGINT,
+        SILType::Float => define::FLOAT,
+        SILType::Bool => define::BOOL,
+        SILType::Vector => define::VECTOR,
+        SILType::Map => define::MAP,
+        SILType::Void => define::VOID,
+        SILType::TypeName | SILType::Any => "any",
+    }
+}
+
+fn type_from_name(name: &str) -> Option<SILType> {
+    if name == define::STRING {
+        Some(SILType::String)
+    } else if name == define::INT {
+        Some(SILType::Int)
+    } else if name == define::BIGINT {
+        Some(SILType::BigInt)
+    } else if name == define::FLOAT {
+        Some(SILType::Float)
+    } else if name == define::BOOL {
+        Some(SILType::Bool)
+    } else if name == define::VECTOR {
+        Some(SILType::Vector)
+    } else if name == define::VOID {
+        Some(SILType::Void)
+    } else {
+        None
+    }
+}
+
+/// Returns true if `cast_value` can convert `from` to `to`
+fn is_castable(from: &SILType, to: &SILType) -> bool {
+    let scalar = |t: &SILType| matches!(t, SILType::String | SILType::Int | SILType::BigInt | SILType::Float);
+    from == to || *from == SILType::Any || *to == SILType::Any || (scalar(from) && scalar(to))
+}
+
+fn is_number(sil_type: &SILType) -> bool {
+    matches!(sil_type, SILType::Int | SILType::BigInt | SILType::Float)
+}
+
+fn type_list(types: &[SILType]) -> String {
+    let names: Vec<_> = types.iter().map(type_name).collect();
+    names.join(" ")
+}
+
+impl<'a> Checker<'a> {
+    fn error(&mut self, span: Span, message: String) {
+        let (line, column) = span.line_column(self.source);
+        self.errors.push(TypeError { line, column, message });
+    }
+
+    fn declare(&mut self, name: &str, binding: Binding) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_owned(), binding);
+        }
+    }
+    fn lookup(&self, name: &str) -> Option<&Binding> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    fn program(&mut self, program: &Program) {
+        for statement in &program.statements {
+            self.expression(&statement.expression);
+        }
+    }
+
+    /// Returns types of values `expression` evaluates to if they are known
+    fn expression(&mut self, expression: &Expression) -> Option<Vec<SILType>> {
+        let (head, args) = expression.factors.split_first()?;
+        if let (Some(name), None) = (&head.identifier, &head.expression) {
+            match self.lookup(name).cloned() {
+                Some(Binding::Function(return_type)) => {
+                    for factor in args {
+                        self.factor(factor);
+                    }
+                    return match return_type {
+                        Some(SILType::Void) => Some(Vec::new()),
+                        Some(sil_type) => Some(vec![sil_type]),
+                        None => None,
+                    }
+                },
+                Some(_) => {},
+                None => return self.builtin(name, head.span, args),
+            }
+        }
+        self.factors(&expression.factors)
+    }
+
+    fn factor(&mut self, factor: &Factor) -> Option<Vec<SILType>> {
+        if factor.string.is_some() {
+            Some(vec![SILType::String])
+        } else if factor.int.is_some() {
+            Some(vec![SILType::Int])
+        } else if factor.float.is_some() {
+            Some(vec![SILType::Float])
+        } else if let Some(identifier) = &factor.identifier {
+            if let Some(index) = &factor.expression {
+                self.expression(index);
+                return None
+            }
+            match self.lookup(identifier) {
+                Some(Binding::Variable(SILType::Any)) => None,
+                Some(Binding::Variable(sil_type)) => Some(vec![sil_type.clone()]),
+                _ => None,
+            }
+        } else if let Some(expression) = &factor.expression {
+            self.expression(expression)
+        } else if let Some(block) = &factor.block {
+            self.scopes.push(HashMap::new());
+            self.program(&block.program);
+            self.scopes.pop();
+            None
+        } else {
+            None
+        }
+    }
+
+    /// Returns types of all values of `factors` if they are known
+    fn factors(&mut self, factors: &[Factor]) -> Option<Vec<SILType>> {
+        let mut types = Some(Vec::new());
+        for factor in factors {
+            let factor_types = self.factor(factor);
+            types = types.and_then(|mut types| {
+                types.append(&mut factor_types?);
+                Some(types)
+            });
+        }
+        types
+    }
+
+    fn builtin(&mut self, name: &str, span: Span, args: &[Factor]) -> Option<Vec<SILType>> {
+        if name == define::DECAS || name == define::DECAS_ALIAS {
+            self.decas(name, span, args);
+            None
+        } else if name == define::ASSIGN || name == define::ASSIGN_DEFER {
+            self.assign(name, span, args, name == define::ASSIGN);
+            None
+        } else if name == define::FUNCTION_DEFINITION {
+            self.define_function(args);
+            None
+        } else if name == define::LAMBDA {
+            if let (Some(params), Some(body)) = (args.first(), args.get(1)) {
+                self.function_body(params, body, None);
+            }
+            None
+        } else if name == define::RETURN {
+            self.return_expression(span, args);
+            None
+        } else if name == define::UNTYPED {
+            None
+        } else if name == define::IF || name == define::LOOP {
+            if let Some(condition) = args.first() {
+                if let Some(types) = self.factor(condition) {
+                    if types != [SILType::Bool] {
+                        self.error(condition.span, format!("{}: Argument 1 must be single bool value, found ({})", name, type_list(&types)));
+                    }
+                }
+            }
+            for factor in args.iter().skip(1) {
+                self.factor(factor);
+            }
+            None
+        } else if name == define::ADD || name == define::SUB || name == define::MUL || name == define::DIV || name == define::REM {
+            let types = self.factors(args)?;
+            if name == define::ADD && !types.is_empty() && types.iter().all(|t| *t == SILType::String) {
+                Some(vec![SILType::String])
+            } else if types.iter().all(is_number) {
+                if types.contains(&SILType::BigInt) {
+                    Some(vec![SILType::BigInt])
+                } else if types.contains(&SILType::Float) {
+                    Some(vec![SILType::Float])
+                } else {
+                    Some(vec![SILType::Int])
+                }
+            } else {
+                self.error(span, format!("{}: {}: ({})", name, define::UNSUPPORTED_OPERATION, type_list(&types)));
+                None
+            }
+        } else if [
+            define::WRAPPING_ADD, define::WRAPPING_SUB, define::WRAPPING_MUL,
+            define::SATURATING_ADD, define::SATURATING_SUB, define::SATURATING_MUL,
+            define::BIT_AND, define::BIT_OR, define::BIT_XOR, define::BIT_NOT,
+            define::SHIFT_LEFT, define::SHIFT_RIGHT, define::SHIFT_RIGHT_LOGICAL,
+            define::POPCOUNT, define::LEADING_ZEROS, define::TRAILING_ZEROS,
+        ].contains(&name) {
+            let types = self.factors(args);
+            if let Some(types) = types {
+                if types.iter().any(|t| *t != SILType::Int && *t != SILType::Float) {
+                    self.error(span, format!("{}: Argument must be int, found ({})", name, type_list(&types)));
+                }
+            }
+            Some(vec![SILType::Int])
+        } else if [define::PRINT, define::PRINTLN, define::EPRINT, define::EPRINTLN].contains(&name) {
+            // Printed values are returned
+            self.factors(args)
+        } else {
+            self.factors(args);
+            if name == define::EQUAL || name == define::EOF || name == define::EXISTS {
+                Some(vec![SILType::Bool])
+            } else if name == define::READ_ALL || name == define::READ_FILE || name == define::ENV {
+                Some(vec![SILType::String])
+            } else if name == define::READ_LINES || name == define::LIST_DIR {
+                Some(vec![SILType::Vector])
+            } else if [
+                define::WRITE_FILE, define::APPEND_FILE, define::REMOVE_FILE, define::MKDIR,
+                define::SET_ENV, define::EXIT,
+            ].contains(&name) {
+                Some(Vec::new())
+            } else {
+                None
+            }
+        }
+    }
+
+    fn decas(&mut self, name: &str, span: Span, args: &[Factor]) {
+        let (lhs, rhs) = match args {
+            [lhs, rhs] => (identifiers(lhs), identifiers(rhs)),
+            _ => return,
+        };
+        if lhs.len() != rhs.len() {
+            self.error(span, format!("{}: LHS and RHS length must be equal", name));
+            return
+        }
+        for (identifier, type_name) in lhs.iter().zip(rhs.iter()) {
+            match type_from_name(type_name) {
+                Some(sil_type) => self.declare(identifier, Binding::Variable(sil_type)),
+                None => self.declare(identifier, Binding::Unknown),
+            }
+        }
+    }
+
+    fn assign(&mut self, name: &str, span: Span, args: &[Factor], evaluate_rhs: bool) {
+        let (lhs, rhs) = match args {
+            [lhs, rhs] => (lhs, rhs),
+            _ => {
+                self.factors(args);
+                return
+            },
+        };
+        let mut lhs_types = Some(Vec::new());
+        let lhs_factors = match &lhs.expression {
+            Some(expression) if lhs.identifier.is_none() => expression.factors.clone(),
+            _ => vec![lhs.clone()],
+        };
+        for factor in &lhs_factors {
+            let sil_type = match (&factor.identifier, &factor.expression) {
+                (Some(identifier), None) => match self.lookup(identifier) {
+                    Some(Binding::Variable(sil_type)) => Some(sil_type.clone()),
+                    Some(_) => None,
+                    None => {
+                        self.declare(identifier, Binding::Unknown);
+                        None
+                    },
+                },
+                _ => {
+                    self.factor(factor);
+                    None
+                },
+            };
+            lhs_types = match (lhs_types, sil_type) {
+                (Some(mut types), Some(sil_type)) => {
+                    types.push(sil_type);
+                    Some(types)
+                },
+                _ => None,
+            };
+        }
+        if !evaluate_rhs {
+            return
+        }
+        let rhs_types = self.factor(rhs);
+        if let (Some(lhs_types), Some(rhs_types)) = (lhs_types, rhs_types) {
+            if lhs_types.len() != rhs_types.len() {
+                self.error(span, format!("{}: LHS and RHS length must be equal", name));
+                return
+            }
+            for (to, from) in lhs_types.iter().zip(rhs_types.iter()) {
+                if !is_castable(from, to) {
+                    self.error(rhs.span, format!("{}: {}: {} to {}", name, define::TYPE_NOT_MATCHED, type_name(from), type_name(to)));
+                }
+            }
+        }
+    }
+
+    fn define_function(&mut self, args: &[Factor]) {
+        let return_type = match args.get(2) {
+            Some(factor) if args.len() == 4 => factor.identifier.as_ref().and_then(|name| type_from_name(name)),
+            _ => None,
+        };
+        if let Some(identifier) = args.first().and_then(|f| f.identifier.as_ref()) {
+            self.declare(identifier, Binding::Function(return_type.clone()));
+        }
+        if let (Some(params), Some(body)) = (args.get(1), args.last()) {
+            if args.len() >= 3 {
+                self.function_body(params, body, return_type);
+            }
+        }
+    }
+    fn function_body(&mut self, params: &Factor, body: &Factor, return_type: Option<SILType>) {
+        let block = match &body.block {
+            Some(block) => block,
+            None => return,
+        };
+        let mut scope = HashMap::new();
+        for param in identifiers(params) {
+            scope.insert(param, Binding::Unknown);
+        }
+        self.scopes.push(scope);
+        self.return_types.push(return_type);
+        self.program(&block.program);
+        self.return_types.pop();
+        self.scopes.pop();
+    }
+
+    fn return_expression(&mut self, span: Span, args: &[Factor]) {
+        let types = self.factors(args);
+        let return_type = match self.return_types.last() {
+            Some(Some(return_type)) => return_type.clone(),
+            _ => return,
+        };
+        let types = match types {
+            Some(types) => types,
+            None => return,
+        };
+        let matched = if return_type == SILType::Void {
+            types.is_empty()
+        } else {
+            types.len() == 1 && is_castable(&types[0], &return_type)
+        };
+        if !matched {
+            self.error(span, format!("{}: {}: ({}) to {}", define::RETURN, define::TYPE_NOT_MATCHED, type_list(&types), type_name(&return_type)));
+        }
+    }
+}
+
+/// Returns identifiers in `x` or `(x y ...)`
+fn identifiers(factor: &Factor) -> Vec<String> {
+    let mut names = Vec::new();
+    if let Some(identifier) = &factor.identifier {
+        names.push(identifier.clone());
+    } else if let Some(expression) = &factor.expression {
+        for f in &expression.factors {
+            names.append(&mut identifiers(f));
+        }
+    }
+    names
+}
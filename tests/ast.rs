@@ -0,0 +1,70 @@
+//! Round-trip tests of JSON and S-expression AST output over `tests/golden/*.sil`
+
+use silang::{
+    ast,
+    parser,
+    preprocessor,
+};
+
+use std::fs;
+use std::path::PathBuf;
+
+static GOLDEN_DIR: &str = "tests/golden";
+
+fn golden_sources() -> Vec<(PathBuf, String)> {
+    let mut files: Vec<PathBuf> = fs::read_dir(GOLDEN_DIR)
+        .expect("golden directory")
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "sil"))
+        .collect();
+    files.sort();
+    files.into_iter().map(|file| {
+        let source = fs::read_to_string(&file).expect("golden file");
+        let source = preprocessor::preprocess(&format!("{}\n", source)).expect("preprocess");
+        (file, source)
+    }).collect()
+}
+
+#[test]
+fn round_trip() {
+    for (file, source) in golden_sources() {
+        let program = parser::program_all_consuming(&source).expect("parse").1;
+        for format in &[ast::JSON, ast::SEXP] {
+            let written = ast::write_program(&program, &source, format).expect("write");
+            let read = ast::read_program(&written, format).unwrap_or_else(|e| panic!("{} {}: {}", file.display(), format, e));
+            assert_eq!(read, program, "{} {}", file.display(), format);
+            // Read program has no source, but keeps positions
+            let rewritten = ast::write_program(&read, "", format).expect("write");
+            assert_eq!(rewritten, written, "{} {}", file.display(), format);
+        }
+    }
+}
+
+#[test]
+fn positions() {
+    let source = "println \"hi\"\nif true {\n    println (+ 1 2)\n}\n";
+    let program = parser::program_all_consuming(source).expect("parse").1;
+    let sexp = ast::write_program(&program, source, ast::SEXP).expect("write");
+    assert!(sexp.starts_with("(program (statement (at 1 1) (expression (identifier (at 1 1) \"println\") (string (at 1 9) \"hi\")))"), "{}", sexp);
+    assert!(sexp.contains("(statement (at 3 5) (expression (identifier (at 3 5) \"println\") (expression (at 3 13)"), "{}", sexp);
+
+    let read = ast::read_program(&sexp, ast::SEXP).expect("read");
+    assert_eq!(read.statements[1].span.line_column(""), (2, 1));
+}
+
+#[test]
+fn invalid_positions() {
+    let far = "(program (statement (at 100000 200000) (expression (identifier (at 100000 200000) \"println\"))))";
+    assert!(ast::read_program(far, ast::SEXP).is_ok());
+    let errors = [
+        ("(program (statement (at 0 1) (expression)))", ast::SEXP),
+        ("(program (statement (at 2 1) (expression)) (statement (at 1 1) (expression)))", ast::SEXP),
+        ("(program (statement (at 1 99999999999999999999) (expression)))", ast::SEXP),
+        ("{\"statements\": [{\"line\": 1, \"expression\": {\"factors\": []}}]}", ast::JSON),
+        ("{\"statements\": [{\"line\": 1, \"column\": -1, \"expression\": {\"factors\": []}}]}", ast::JSON),
+    ];
+    for (input, format) in &errors {
+        assert!(ast::read_program(input, format).is_err(), "{}", input);
+    }
+}
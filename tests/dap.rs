@@ -0,0 +1,126 @@
+//! Scripted DAP session with `silang-dap` over stdio
+
+use silang::lsp::{
+    read_message,
+    write_message,
+};
+
+use serde_json::{
+    json,
+    Value as Json,
+};
+
+use std::fs;
+use std::io::BufReader;
+use std::process::{
+    ChildStdin,
+    ChildStdout,
+    Command,
+    Stdio,
+};
+
+static PROGRAM: &str = "\
+:: total int
+= total 0
+f: add (a b) int {
+    return (+ a b)
+}
+= total (add 2 3)
+println total
+";
+
+struct Session {
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    seq: i64,
+    /// Program output received so far
+    output: String,
+}
+impl Session {
+    fn request(&mut self, command: &str, arguments: Json) -> Json {
+        self.seq += 1;
+        let request = json!({ "seq": self.seq, "type": "request", "command": command, "arguments": arguments });
+        write_message(&mut self.stdin, &request).expect("write");
+        let seq = self.seq;
+        let response = self.receive(|message| message["type"] == "response" && message["request_seq"] == seq);
+        assert_eq!(response["success"], true, "{}", response);
+        response["body"].clone()
+    }
+    /// Reads messages until `predicate` matches, collecting output events
+    fn receive(&mut self, predicate: impl Fn(&Json) -> bool) -> Json {
+        loop {
+            let message = read_message(&mut self.stdout).expect("read").expect("message");
+            if message["event"] == "output" {
+                self.output.push_str(message["body"]["output"].as_str().unwrap_or(""));
+            }
+            if predicate(&message) {
+                return message
+            }
+        }
+    }
+    fn event(&mut self, name: &str) -> Json {
+        self.receive(|message| message["type"] == "event" && message["event"] == name)["body"].clone()
+    }
+}
+
+#[test]
+fn session() {
+    let program = std::env::temp_dir().join(format!("silang-dap-{}.sil", std::process::id()));
+    fs::write(&program, PROGRAM).expect("write program");
+    let path = program.to_string_lossy().into_owned();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_silang-dap"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("spawn silang-dap");
+    let mut session = Session {
+        stdin: child.stdin.take().expect("stdin"),
+        stdout: BufReader::new(child.stdout.take().expect("stdout")),
+        seq: 0,
+        output: String::new(),
+    };
+
+    let capabilities = session.request("initialize", json!({ "adapterID": "silang" }));
+    assert_eq!(capabilities["supportsConfigurationDoneRequest"], true);
+    session.event("initialized");
+    session.request("launch", json!({ "program": path }));
+    let breakpoints = session.request("setBreakpoints", json!({ "source": { "path": path }, "breakpoints": [{ "line": 4 }] }));
+    assert_eq!(breakpoints["breakpoints"], json!([{ "verified": true, "line": 4 }]));
+    session.request("configurationDone", json!({}));
+
+    let stopped = session.event("stopped");
+    assert_eq!(stopped["reason"], "breakpoint");
+    let stack = session.request("stackTrace", json!({ "threadId": stopped["threadId"] }));
+    let frames = stack["stackFrames"].as_array().expect("frames");
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0]["name"], "add");
+    assert_eq!(frames[0]["line"], 4);
+    assert_eq!(frames[1]["line"], 6);
+
+    let scopes = session.request("scopes", json!({ "frameId": frames[0]["id"] }));
+    let scopes = scopes["scopes"].as_array().expect("scopes");
+    assert_eq!(scopes[0]["name"], "Function");
+    assert_eq!(scopes[0]["presentationHint"], "locals");
+    let locals = session.request("variables", json!({ "variablesReference": scopes[0]["variablesReference"] }));
+    let names: Vec<(&str, &str)> = locals["variables"].as_array().expect("variables").iter()
+        .map(|v| (v["name"].as_str().unwrap_or(""), v["value"].as_str().unwrap_or("")))
+        .collect();
+    assert_eq!(names, [("a", "2"), ("b", "3")]);
+    let globals = scopes.iter().find(|scope| scope["name"] == "Globals").expect("globals");
+    let globals = session.request("variables", json!({ "variablesReference": globals["variablesReference"] }));
+    let total = globals["variables"].as_array().expect("variables").iter()
+        .find(|v| v["name"] == "total")
+        .expect("total");
+    assert_eq!(total["value"], "0");
+
+    session.request("continue", json!({ "threadId": stopped["threadId"] }));
+    assert_eq!(session.event("exited")["exitCode"], 0);
+    session.event("terminated");
+    assert_eq!(session.output, "5\n");
+    session.request("disconnect", json!({}));
+
+    let status = child.wait().expect("wait");
+    fs::remove_file(&program).expect("remove program");
+    assert_eq!(status.code(), Some(0));
+}
@@ -0,0 +1,39 @@
+//! Formatter tests over `tests/golden/*.sil`
+
+use silang::{
+    formatter,
+    parser,
+    preprocessor,
+};
+
+use std::fs;
+use std::path::PathBuf;
+
+static GOLDEN_DIR: &str = "tests/golden";
+
+fn golden_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(GOLDEN_DIR)
+        .expect("golden directory")
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "sil"))
+        .collect();
+    files.sort();
+    files
+}
+
+fn parse(source: &str) -> parser::Program {
+    let source = preprocessor::preprocess(&format!("{}\n", source)).expect("preprocess");
+    parser::program_all_consuming(&source).expect("parse").1
+}
+
+#[test]
+fn idempotent_and_unchanged_parse() {
+    for file in golden_files() {
+        let source = fs::read_to_string(&file).expect("golden file");
+        let formatted = formatter::format(&source).unwrap_or_else(|e| panic!("{}: {}", file.display(), e));
+        assert_eq!(parse(&formatted), parse(&source), "{}", file.display());
+        let reformatted = formatter::format(&formatted).unwrap_or_else(|e| panic!("{}: {}", file.display(), e));
+        assert_eq!(reformatted, formatted, "{}", file.display());
+    }
+}
@@ -0,0 +1,171 @@
+//! Golden-file conformance tests
+//!
+//! Runs every `tests/golden/*.sil` through the interpreter and compares
+//! what it prints with `NAME.out`, and what it prints to stderr followed by
+//! the runtime error and exit status with `NAME.err`.
+//! A missing expected file means no output, and `NAME.in` is read as stdin if present.
+//! Set `SILANG_BLESS=1` to rewrite expected files from the current behavior.
+//! Every file is also run with `--trace`, which must not change the output.
+
+use silang::silang::Interpreter;
+use silang::trace::Tracer;
+use silang::{
+    builtin,
+    define,
+    parser,
+    preprocessor,
+};
+
+use std::cell::RefCell;
+use std::collections::{
+    BTreeSet,
+    VecDeque,
+};
+use std::fs;
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+static GOLDEN_DIR: &str = "tests/golden";
+static BLESS: &str = "SILANG_BLESS";
+/// Stack size of the main thread, which the default depth limit fits in
+static STACK_SIZE: usize = 8 * 1024 * 1024;
+
+thread_local! {
+    static STDOUT: RefCell<String> = const { RefCell::new(String::new()) };
+    static STDERR: RefCell<String> = const { RefCell::new(String::new()) };
+    static STDIN: RefCell<VecDeque<String>> = const { RefCell::new(VecDeque::new()) };
+}
+
+fn golden_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(GOLDEN_DIR)
+        .expect("golden directory")
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "sil"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Runs `file`, with tracer discarding its log if `trace` is true, and returns its stdout and stderr
+fn run_golden(file: &Path, trace: bool) -> (String, String) {
+    STDOUT.with(|s| s.borrow_mut().clear());
+    STDERR.with(|s| s.borrow_mut().clear());
+    let input = fs::read_to_string(file.with_extension("in")).unwrap_or_default();
+    STDIN.with(|s| *s.borrow_mut() = input.split_inclusive('\n').map(|line| line.to_owned()).collect());
+
+    let mut interpreter = Interpreter::new();
+    interpreter.stdout_func = |_, data| STDOUT.with(|s| s.borrow_mut().push_str(data));
+    interpreter.stderr_func = |_, data| STDERR.with(|s| s.borrow_mut().push_str(data));
+    interpreter.stdin_func = |_| STDIN.with(|s| s.borrow_mut().pop_front());
+    if trace {
+        interpreter.tracer = Some(Tracer::new(Box::new(std::io::sink()), Vec::new()));
+    }
+
+    let mut buffer = fs::read_to_string(file).expect("golden file");
+    buffer.push('\n');
+    let status = match preprocessor::preprocess(&buffer) {
+        Err(e) => {
+            STDERR.with(|s| s.borrow_mut().push_str(&format!("Preprocess error\n{}\n", e)));
+            Some(define::EXIT_PREPROCESS_ERROR)
+        },
+        Ok(source_code) => match parser::program_all_consuming(&source_code) {
+            Err(_) => {
+                STDERR.with(|s| s.borrow_mut().push_str("Parse error\n"));
+                Some(define::EXIT_PARSE_ERROR)
+            },
+            Ok((_, program)) => {
+                interpreter.current_source = Some(interpreter.add_source_file(&file.to_string_lossy(), &source_code));
+                let result = interpreter.run(&program);
+                match (interpreter.exit_status, result) {
+                    (Some(status), _) => Some(status),
+                    (None, Err(e)) => {
+                        STDERR.with(|s| s.borrow_mut().push_str(&format!("{}\n", e)));
+                        Some(define::EXIT_RUNTIME_ERROR)
+                    },
+                    (None, Ok(_)) => None,
+                }
+            },
+        },
+    };
+    if let Some(status) = status.filter(|status| *status != 0) {
+        STDERR.with(|s| s.borrow_mut().push_str(&format!("exit status {}\n", status)));
+    }
+    (STDOUT.with(|s| s.borrow().clone()), STDERR.with(|s| s.borrow().clone()))
+}
+
+/// Runs `file` on a thread with as much stack as the main thread of `silang`
+fn run_golden_thread(file: &Path, trace: bool) -> (String, String) {
+    let file = file.to_owned();
+    std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(move || run_golden(&file, trace))
+        .expect("spawn golden thread")
+        .join()
+        .expect("golden thread")
+}
+
+/// Compares `actual` with `expected` file, or rewrites it when blessing
+fn check(expected: &Path, actual: &str, bless: bool, failures: &mut Vec<String>) {
+    if bless {
+        if actual.is_empty() {
+            fs::remove_file(expected).ok();
+        } else {
+            fs::write(expected, actual).expect("write expected file");
+        }
+        return
+    }
+    let expected_content = fs::read_to_string(expected).unwrap_or_default();
+    if expected_content != actual {
+        failures.push(format!(
+            "{}\n--- expected\n{}--- actual\n{}",
+            expected.display(),
+            expected_content,
+            actual,
+        ));
+    }
+}
+
+#[test]
+fn golden() {
+    let bless = std::env::var_os(BLESS).is_some();
+    let mut failures = Vec::new();
+    for file in golden_files() {
+        let (stdout, stderr) = run_golden_thread(&file, false);
+        check(&file.with_extension("out"), &stdout, bless, &mut failures);
+        check(&file.with_extension("err"), &stderr, bless, &mut failures);
+        if run_golden_thread(&file, true) != (stdout, stderr) {
+            failures.push(format!("{}\noutput differs with trace", file.display()));
+        }
+    }
+    assert!(failures.is_empty(), "{} golden file(s) differ, run with {}=1 to update\n\n{}", failures.len(), BLESS, failures.join("\n"));
+}
+
+/// Every builtin function and type name must be used outside comments in some golden file
+#[test]
+fn every_builtin_is_covered() {
+    let mut identifiers = BTreeSet::new();
+    for file in golden_files() {
+        let source = fs::read_to_string(file).expect("golden file");
+        let source = preprocessor::preprocess(&format!("{}\n", source)).unwrap_or_default();
+        for token in source.split(|c| define::PARSER_NOT_IDENTIFIER.contains(c)) {
+            identifiers.insert(token.to_owned());
+        }
+    }
+    let mut names: Vec<&str> = builtin::builtin_functions().iter().map(|function| function.name).collect();
+    names.extend(&[
+        define::STRING,
+        define::INT,
+        define::BIGINT,
+        define::FLOAT,
+        define::BOOL,
+        define::VECTOR,
+        define::VOID,
+        define::TRUE,
+        define::FALSE,
+    ]);
+    let missing: Vec<&str> = names.into_iter().filter(|name| !identifiers.contains(*name)).collect();
+    assert!(missing.is_empty(), "not covered by golden files: {:?}", missing);
+}
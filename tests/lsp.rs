@@ -0,0 +1,104 @@
+//! JSON-RPC session with `silang-lsp` over stdio
+
+use silang::lsp::{
+    read_message,
+    write_message,
+};
+
+use serde_json::{
+    json,
+    Value as Json,
+};
+
+use std::fs;
+use std::io::BufReader;
+use std::process::{
+    Command,
+    Stdio,
+};
+
+static DOCUMENT: &str = "\
+= x 1
+f: show (x) {
+    println x
+}
+if true {
+    = y 2
+    println y
+}
+if true {
+    = y \"s\"
+}
+import \"lsp_module\"
+println x helper
+";
+
+fn request(id: i64, method: &str, params: Json) -> Json {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+fn position(uri: &str, line: usize, character: usize) -> Json {
+    json!({ "textDocument": { "uri": uri }, "position": { "line": line, "character": character } })
+}
+fn start(definition: &Json) -> (u64, u64) {
+    let start = &definition["range"]["start"];
+    (start["line"].as_u64().expect("line"), start["character"].as_u64().expect("character"))
+}
+
+#[test]
+fn session() {
+    // Modules are resolved relative to the working directory like `import`
+    let directory = std::env::temp_dir().join(format!("silang-lsp-{}", std::process::id()));
+    fs::create_dir_all(&directory).expect("create directory");
+    fs::write(directory.join("lsp_module.sil"), "f: helper (a) {\n    a\n}\n").expect("write module");
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_silang-lsp"))
+        .current_dir(&directory)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("spawn silang-lsp");
+    let mut stdin = child.stdin.take().expect("stdin");
+    let mut stdout = BufReader::new(child.stdout.take().expect("stdout"));
+    let mut call = |message: Json| -> Json {
+        write_message(&mut stdin, &message).expect("write");
+        read_message(&mut stdout).expect("read").expect("response")
+    };
+
+    let initialize = call(request(1, "initialize", json!({ "capabilities": {} })));
+    assert_eq!(initialize["result"]["capabilities"]["definitionProvider"], true);
+
+    let uri = "file:///elsewhere/main.sil";
+    let opened = call(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": { "textDocument": { "uri": uri, "languageId": "silang", "version": 1, "text": DOCUMENT } },
+    }));
+    assert_eq!(opened["method"], "textDocument/publishDiagnostics");
+    assert_eq!(opened["params"]["diagnostics"], json!([]));
+
+    // Parameter shadows the global inside the function
+    let shadowed = call(request(2, "textDocument/definition", position(uri, 2, 12)));
+    assert_eq!(start(&shadowed["result"]), (1, 9));
+    let global = call(request(3, "textDocument/definition", position(uri, 12, 8)));
+    assert_eq!(start(&global["result"]), (0, 2));
+
+    // Locals of sibling blocks are separate
+    let first = call(request(4, "textDocument/definition", position(uri, 6, 12)));
+    assert_eq!(start(&first["result"]), (5, 6));
+    let second = call(request(5, "textDocument/definition", position(uri, 9, 6)));
+    assert_eq!(start(&second["result"]), (9, 6));
+
+    let hover = call(request(6, "textDocument/hover", position(uri, 12, 11)));
+    assert_eq!(hover["result"]["contents"]["value"], "f: helper (a)");
+    let imported = call(request(7, "textDocument/definition", position(uri, 12, 11)));
+    let module = directory.join("lsp_module.sil");
+    assert_eq!(imported["result"]["uri"], format!("file://{}", module.display()));
+    assert_eq!(start(&imported["result"]), (0, 3));
+
+    let shutdown = call(request(8, "shutdown", Json::Null));
+    assert_eq!(shutdown["result"], Json::Null);
+    write_message(&mut stdin, &json!({ "jsonrpc": "2.0", "method": "exit" })).expect("write");
+    let status = child.wait().expect("wait");
+    fs::remove_dir_all(&directory).expect("remove directory");
+    assert_eq!(status.code(), Some(0));
+}
@@ -0,0 +1,61 @@
+//! Type checker tests of accepted and rejected programs
+
+use silang::{
+    parser,
+    preprocessor,
+    typecheck,
+};
+
+/// Returns type errors of `source` as `line:column: message`
+fn check(source: &str) -> Vec<String> {
+    let source = preprocessor::preprocess(&format!("{}\n", source)).expect("preprocess");
+    let program = parser::program_all_consuming(&source).expect("parse").1;
+    typecheck::check(&source, &program).into_iter()
+        .map(|e| format!("{}:{}: {}", e.line, e.column, e.message))
+        .collect()
+}
+
+#[test]
+fn accepted() {
+    let programs = [
+        // Scalars are castable to each other
+        ":: (a s) (int string)\n= a 1\n= s \"x\"\n= s a\n",
+        "f: add1 (x) int {\n    return (+ x 1)\n}\n:: n int\n= n (add1 2)\n",
+        "f: greet (name) void {\n    println \"hello \" name\n    return\n}\ngreet \"world\"\n",
+        "if (== 1 1) {\n    println 1\n} {\n    println 2\n}\n",
+        // Declarations are scoped to their block
+        "if (true) {\n    :: t bool\n    = t true\n}\n= t \"x\"\n",
+        // Untyped blocks and unknown types are not checked
+        "untyped {\n    :: b bool\n    = b \"x\"\n}\n",
+        ":: b bool\n= b (read_line)\n",
+        "= (x y) (1 \"a\")\nprintln (+ x y)\n",
+        // print returns printed values
+        ":: (s b) (string bool)\n= (s b) (print \"x\" true)\n",
+    ];
+    for program in &programs {
+        assert_eq!(check(program), Vec::<String>::new(), "{}", program);
+    }
+}
+
+#[test]
+fn rejected() {
+    let programs: &[(&str, &[&str])] = &[
+        (":: b bool\n= b \"x\"\n", &["2:5: =: Type not matched: string to bool"]),
+        (":: (a b) (int int)\n= (a b) (1)\n", &["2:1: =: LHS and RHS length must be equal"]),
+        (":: (a b) (int)\n", &["1:1: ::: LHS and RHS length must be equal"]),
+        ("if (+ 1 2) {\n    println 1\n}\n", &["1:4: if: Argument 1 must be single bool value, found (float)"]),
+        ("loop \"yes\" {\n    println 1\n}\n", &["1:6: loop: Argument 1 must be single bool value, found (string)"]),
+        ("println (+ \"a\" true)\n", &["1:10: +: Unsupported operation: (string bool)"]),
+        (":: b bool\n= b (println 1)\n", &["2:5: =: Type not matched: float to bool"]),
+        ("println (& 1 \"a\")\n", &["1:10: &: Argument must be int, found (float string)"]),
+        ("f: g () int {\n    return true\n}\n", &["2:5: return: Type not matched: (bool) to int"]),
+        ("f: h () void {\n    return 1\n}\n", &["2:5: return: Type not matched: (float) to void"]),
+        (
+            "f: k () bool {\n    return 1\n}\n:: s string\n= s (k)\n",
+            &["2:5: return: Type not matched: (float) to bool", "5:5: =: Type not matched: bool to string"],
+        ),
+    ];
+    for (program, errors) in programs {
+        assert_eq!(check(program), *errors, "{}", program);
+    }
+}